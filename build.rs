@@ -37,7 +37,65 @@ fn compile_windows_icon() {
     }
 }
 
+/// Tries to compile a SIMD variant, and on failure warns and skips it instead of
+/// aborting the whole build. Borrowed from BLAKE3's `cc::Build::try_compile`
+/// fallback, this keeps the crate buildable on older MSVC/cross-gcc toolchains
+/// that don't understand a given `-m*`/`/arch:*` flag.
+///
+/// On success, emits `cargo:rustc-cfg=have_<name>` so the Rust dispatch layer in
+/// `cpu_worker` only matches on variants that actually got linked in.
+fn try_compile_variant(name: &str, config: cc::Build, files: &[&str]) {
+    let mut config = config;
+    for file in files {
+        config.file(file);
+    }
+
+    match config.try_compile(name) {
+        Ok(()) => {
+            println!("cargo:rustc-cfg=have_{}", name);
+        }
+        Err(e) => {
+            println!(
+                "cargo:warning=skipping '{}': compiler rejected the required flags ({}). \
+                 Falling back to the baseline Shabal implementation for this variant.",
+                name, e
+            );
+        }
+    }
+}
+
+/// Shabal and the Signum nonce/deadline layout are defined little-endian; on a
+/// big-endian target the miner would silently compute wrong deadlines instead of
+/// failing loudly. Following BLAKE3's explicit `CARGO_CFG_TARGET_ENDIAN` check, we
+/// refuse to build for big-endian targets rather than shipping something broken.
+fn check_endianness() {
+    let endian = env::var("CARGO_CFG_TARGET_ENDIAN").unwrap_or_default();
+    if endian == "big" {
+        panic!(
+            "signum-miner does not support big-endian targets: Shabal and the \
+             plot/nonce/deadline layout are little-endian-only and would silently \
+             produce incorrect deadlines. Build for a little-endian target instead."
+        );
+    }
+    // Little-endian is the only supported layout, so the hashing modules have
+    // nothing to branch on; this `cfg` only lets them assert that the check ran.
+    println!("cargo:rustc-cfg=shabal_little_endian");
+}
+
 fn main() {
+    check_endianness();
+
+    // The `pure` feature mirrors BLAKE3's: it skips every `cc::Build` invocation
+    // below so the crate links the pure-Rust `poc_hashing::find_best_deadline_rust`
+    // path exclusively, with no C toolchain required at all. This matters for
+    // cross-compilation and locked-down CI where a C compiler isn't available.
+    // `cpu_worker::simd_variant` already falls back to that path whenever none of
+    // the `have_shabal_*` cfgs below are emitted, so nothing else needs to change.
+    if env::var("CARGO_FEATURE_PURE").is_ok() {
+        println!("cargo:warning=building with --features pure: Shabal runs entirely in Rust, no C toolchain required");
+        return;
+    }
+
     let mut shared_config = cc::Build::new();
 
     #[cfg(target_env = "gnu")]
@@ -69,101 +127,91 @@ fn main() {
         .file("src/c/common.c")
         .compile("shabal");
 
-    // **Detect which SIMD features are enabled**
-    let simd_sse2    = env::var("CARGO_FEATURE_SIMD_SSE2").is_ok();
-    let simd_avx     = env::var("CARGO_FEATURE_SIMD_AVX").is_ok();
-    let simd_avx2    = env::var("CARGO_FEATURE_SIMD_AVX2").is_ok();
-    let simd_avx512f = env::var("CARGO_FEATURE_SIMD_AVX512F").is_ok();
-
-    // **Ensure that at most one SIMD variant is active** â€“ if more than one is enabled, stop with an error.
-    let simd_count = [simd_sse2, simd_avx, simd_avx2, simd_avx512f]
-        .iter()
-        .filter(|&&enabled| enabled)
-        .count();
-    if simd_count > 1 {
-        panic!(
-            "Multiple SIMD features enabled ({:?}). Please activate only one of 'simd_sse2', 'simd_avx', 'simd_avx2', or 'simd_avx512f'.",
-            [
-                (simd_sse2, "simd_sse2"),
-                (simd_avx, "simd_avx"),
-                (simd_avx2, "simd_avx2"),
-                (simd_avx512f, "simd_avx512f")
-            ]
-            .iter()
-            .filter_map(|&(enabled, name)| if enabled { Some(name) } else { None })
-            .collect::<Vec<&str>>()
+    // **Runtime dispatch**: rather than asking the user to pick exactly one SIMD
+    // variant at compile time, compile every x86 variant we have kernels for and
+    // let `cpu_worker::simd_variant` pick the best one at startup via
+    // `is_x86_feature_detected!`. This mirrors BLAKE3's approach of shipping one
+    // portable binary instead of a rebuild per target CPU.
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+
+    if target_arch == "x86" || target_arch == "x86_64" {
+        let mut config = base_config.clone();
+        if !cfg!(target_env = "msvc") {
+            config.flag("-msse2");
+        }
+        try_compile_variant(
+            "shabal_sse2",
+            config,
+            &["src/c/mshabal_128_sse2.c", "src/c/shabal_sse2.c"],
         );
-    }
 
-    // **Compile the selected SIMD variant (if exactly one is enabled)**
-    if simd_count == 1 {
         let mut config = base_config.clone();
-        if simd_sse2 {
-            // Enable SSE2 intrinsics
-            if !cfg!(target_env = "msvc") {
-                config.flag("-msse2");
-            }
-            config
-                .file("src/c/mshabal_128_sse2.c")
-                .file("src/c/shabal_sse2.c")
-                .compile("shabal_sse2");
-        } else if simd_avx {
-            // Enable AVX intrinsics
-            if cfg!(target_env = "msvc") {
-                config.flag("/arch:AVX");
-            } else {
-                config.flag("-mavx");
-            }
-            config
-                .file("src/c/mshabal_128_avx.c")
-                .file("src/c/shabal_avx.c")
-                .compile("shabal_avx");
-        } else if simd_avx2 {
-            // Enable AVX2 intrinsics
-            if cfg!(target_env = "msvc") {
-                config.flag("/arch:AVX2");
-            } else {
-                config.flag("-mavx2");
-            }
-            config
-                .file("src/c/mshabal_256_avx2.c")
-                .file("src/c/shabal_avx2.c")
-                .compile("shabal_avx2");
-        } else if simd_avx512f {
-            // Enable AVX-512F intrinsics
-            if cfg!(target_env = "msvc") {
-                config.flag("/arch:AVX512");
-            } else {
-                config.flag("-mavx512f");
-            }
-            config
-                .file("src/c/mshabal_512_avx512f.c")
-                .file("src/c/shabal_avx512f.c")
-                .compile("shabal_avx512f");
+        if cfg!(target_env = "msvc") {
+            config.flag("/arch:AVX");
+        } else {
+            config.flag("-mavx");
         }
-    }
-    // If the umbrella "simd" feature is enabled *without* any specific subfeature, 
-    // no SIMD variant will be compiled (simd_count == 0, so nothing to do here).
-
-    // **Compile Neon variant (independently selectable)**
-    if env::var("CARGO_FEATURE_NEON").is_ok() {
-        // Only attempt Neon build for ARM targets:
-        let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
-        if target_arch == "arm" || target_arch == "aarch64" {
-            let mut config = base_config.clone();
-            // On 32-bit ARM (not AArch64 and not MSVC), use Neon FPU flag
-            if target_arch == "arm" && !cfg!(target_env = "msvc") {
-                config.flag("-mfpu=neon");
-            }
-            config
-                .file("src/c/mshabal_128_neon.c")
-                .file("src/c/shabal_neon.c")
-                .compile("shabal_neon");
+        try_compile_variant(
+            "shabal_avx",
+            config,
+            &["src/c/mshabal_128_avx.c", "src/c/shabal_avx.c"],
+        );
+
+        let mut config = base_config.clone();
+        if cfg!(target_env = "msvc") {
+            config.flag("/arch:AVX2");
         } else {
-            println!(
-                "cargo:warning=Feature 'neon' was enabled for target arch '{}', but Neon is only supported on ARM. Skipping Neon build.",
-                target_arch
-            );
+            config.flag("-mavx2");
+        }
+        try_compile_variant(
+            "shabal_avx2",
+            config,
+            &["src/c/mshabal_256_avx2.c", "src/c/shabal_avx2.c"],
+        );
+
+        let mut config = base_config.clone();
+        if cfg!(target_env = "msvc") {
+            config.flag("/arch:AVX512");
+        } else {
+            config.flag("-mavx512f");
+        }
+        try_compile_variant(
+            "shabal_avx512f",
+            config,
+            &["src/c/mshabal_512_avx512f.c", "src/c/shabal_avx512f.c"],
+        );
+    }
+
+    // **Compile Neon variant on ARM targets.** Unlike x86, there is only one ARM
+    // SIMD kernel, so it is always selected by `cpu_worker::simd_variant` when available.
+    if target_arch == "arm" || target_arch == "aarch64" {
+        let mut config = base_config.clone();
+        // On 32-bit ARM (not AArch64 and not MSVC), use Neon FPU flag
+        if target_arch == "arm" && !cfg!(target_env = "msvc") {
+            config.flag("-mfpu=neon");
         }
+        try_compile_variant(
+            "shabal_neon",
+            config,
+            &["src/c/mshabal_128_neon.c", "src/c/shabal_neon.c"],
+        );
+    }
+
+    // **WASM SIMD128 variant**, gated behind the `wasm32_simd` feature. Unlike the
+    // x86 variants this never participates in runtime feature detection: if the
+    // feature is on we assume `simd128` is available in the target wasm runtime
+    // (wasmtime, browsers), otherwise the scalar baseline is used. This lets the
+    // deadline-scanning core run inside WASM for PoC verification tooling.
+    if target_arch == "wasm32" && env::var("CARGO_FEATURE_WASM32_SIMD").is_ok() {
+        let mut config = base_config.clone();
+        config.flag("-msimd128");
+        try_compile_variant(
+            "shabal_wasm32_simd128",
+            config,
+            &[
+                "src/c/mshabal_128_wasm32_simd128.c",
+                "src/c/shabal_wasm32_simd128.c",
+            ],
+        );
     }
 }
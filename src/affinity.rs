@@ -0,0 +1,89 @@
+//! Optional CPU core pinning for reader and hasher worker threads. Left to
+//! the OS scheduler, a worker can end up sharing a core with something else
+//! between runs, which makes round-time/speed numbers hard to compare
+//! session to session. Pinning is best-effort: a platform or config
+//! `core_affinity` can't make sense of just falls back to the unpinned
+//! default rather than failing the miner.
+
+use core_affinity::CoreId;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// How worker threads should be pinned to CPU cores, mirroring the
+/// `cpu_core_pinning` config value: disabled, spread across every core the
+/// OS reports, or pinned round-robin across an explicit set of core ids.
+#[derive(Clone, Debug)]
+pub enum CorePinning {
+    Disabled,
+    Auto,
+    Explicit(Vec<usize>),
+}
+
+impl Default for CorePinning {
+    fn default() -> Self {
+        CorePinning::Disabled
+    }
+}
+
+/// Hands out one core id per worker, round-robin, and pins the calling
+/// thread to it. Cheap to clone and share across every thread it assigns to.
+#[derive(Clone)]
+pub struct CoreAssigner {
+    cores: Vec<CoreId>,
+    next: Arc<AtomicUsize>,
+}
+
+impl CoreAssigner {
+    /// Builds an assigner for `pinning`, or `None` if pinning is disabled,
+    /// or `core_affinity` can't enumerate any cores on this platform, or
+    /// (for an explicit id list) none of the configured ids actually exist.
+    pub fn new(pinning: &CorePinning) -> Option<Self> {
+        if matches!(pinning, CorePinning::Disabled) {
+            return None;
+        }
+
+        let available = core_affinity::get_core_ids().unwrap_or_default();
+        if available.is_empty() {
+            warn!("cpu_core_pinning: core_affinity could not enumerate any CPU cores on this platform, leaving worker threads unpinned");
+            return None;
+        }
+
+        let cores = match pinning {
+            CorePinning::Disabled => unreachable!(),
+            CorePinning::Auto => available,
+            CorePinning::Explicit(ids) => {
+                let explicit: Vec<CoreId> =
+                    available.into_iter().filter(|c| ids.contains(&c.id)).collect();
+                if explicit.is_empty() {
+                    warn!(
+                        "cpu_core_pinning: none of the configured core ids {:?} are available on this machine, leaving worker threads unpinned",
+                        ids
+                    );
+                    return None;
+                }
+                explicit
+            }
+        };
+
+        Some(CoreAssigner {
+            cores,
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Pins the calling thread to the next core in the rotation and logs the
+    /// choice once under `worker_name`. No-ops (with a warning) if the
+    /// underlying `core_affinity` call fails.
+    pub fn pin_current_thread(&self, worker_name: &str) {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.cores.len();
+        let core = self.cores[idx];
+        if core_affinity::set_for_current(core) {
+            info!("{} pinned to CPU core {}", worker_name, core.id);
+        } else {
+            warn!(
+                "{} failed to pin to CPU core {}, leaving scheduling to the OS",
+                worker_name, core.id
+            );
+        }
+    }
+}
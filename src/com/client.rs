@@ -1,4 +1,5 @@
 use crate::com::api::*;
+use crate::metrics::Metrics;
 use reqwest::{Client as InnerClient, header::{HeaderMap, HeaderName}};
 #[cfg(feature = "async_io")]
 use tokio::sync::Mutex;
@@ -6,25 +7,57 @@ use tokio::sync::Mutex;
 use std::sync::Mutex;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use url::form_urlencoded::byte_serialize;
 use url::Url;
 use hostname::get;
 
-/// A client for communicating with Pool/Proxy/Wallet.
+/// How often the background health task probes the active endpoint (and the
+/// primary, if some backup is currently active) with a lightweight
+/// `getMiningInfo` request.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Consecutive failed health probes before an endpoint is marked down and
+/// the client fails over to the next reachable one in the list.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// A client for communicating with Pool/Proxy/Wallet. Supports a primary
+/// endpoint plus an ordered list of backups: a background health task probes
+/// the active endpoint, fails over to the next reachable one after
+/// `MAX_CONSECUTIVE_FAILURES` in a row, and fails back to the primary on the
+/// first health probe that finds it reachable again, so callers never have
+/// to notice an endpoint going down.
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: InnerClient,
     account_id_to_secret_phrase: Arc<HashMap<u64, String>>,
-    base_uri: Url,
+    /// Primary endpoint first, backups after, in failover order.
+    endpoints: Arc<Vec<Url>>,
+    /// Index into `endpoints` that `uri_for` currently resolves against.
+    active_endpoint: Arc<AtomicUsize>,
+    /// Reset to 0 by any successful request or health probe against the
+    /// active endpoint; read (and reset) by the health task when it decides
+    /// to fail over.
+    consecutive_failures: Arc<AtomicU32>,
     total_size_gb: usize,
     proxy_details: ProxyDetails,
     headers: Arc<Mutex<HeaderMap>>,
+    /// Latency/counter tracking for this client's own requests, shared
+    /// across clones the same way `headers` is.
+    metrics: Arc<Metrics>,
 }
 
 /// Parameters used for nonce submission.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// `attempt` and `first_seen` track this submission's own retry history (see
+/// `RequestHandler::handle_submissions`) so that bookkeeping travels with the
+/// submission itself instead of living in a side table keyed by
+/// `(account_id, height, deadline)`. They're deliberately excluded from
+/// `PartialEq`/`Eq` (implemented manually below) so that `PrioRetry` still
+/// recognizes a resubmission of the same nonce as "the same item" regardless
+/// of how many times it's been retried.
+#[derive(Clone, Debug)]
 pub struct SubmissionParameters {
     pub account_id: u64,
     pub nonce: u64,
@@ -33,8 +66,24 @@ pub struct SubmissionParameters {
     pub deadline_unadjusted: u64,
     pub deadline: u64,
     pub gen_sig: [u8; 32],
+    pub attempt: u32,
+    pub first_seen: Instant,
 }
 
+impl PartialEq for SubmissionParameters {
+    fn eq(&self, other: &Self) -> bool {
+        self.account_id == other.account_id
+            && self.nonce == other.nonce
+            && self.height == other.height
+            && self.block == other.block
+            && self.deadline_unadjusted == other.deadline_unadjusted
+            && self.deadline == other.deadline
+            && self.gen_sig == other.gen_sig
+    }
+}
+
+impl Eq for SubmissionParameters {}
+
 /// Usefull for deciding which submission parameters are the newest and best.
 impl Ord for SubmissionParameters {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -106,35 +155,162 @@ impl Client {
 
     pub fn new(
         base_uri: Url,
+        secret_phrases: HashMap<u64, String>,
+        timeout: u64,
+        total_size_gb: usize,
+        proxy_details: ProxyDetails,
+        additional_headers: HashMap<String, String>,
+    ) -> Self {
+        Self::with_endpoints(
+            vec![base_uri],
+            secret_phrases,
+            timeout,
+            total_size_gb,
+            proxy_details,
+            additional_headers,
+        )
+    }
+
+    /// Like [`Client::new`], but takes an ordered list of endpoints: the
+    /// first is the primary, the rest are backups tried (and automatically
+    /// failed back from) in order. A background health task is only spawned
+    /// when there's more than one endpoint to fail over between.
+    pub fn with_endpoints(
+        endpoints: Vec<Url>,
         mut secret_phrases: HashMap<u64, String>,
         timeout: u64,
         total_size_gb: usize,
         proxy_details: ProxyDetails,
         additional_headers: HashMap<String, String>,
     ) -> Self {
+        assert!(!endpoints.is_empty(), "at least one endpoint must be configured");
+
         for secret_phrase in secret_phrases.values_mut() {
             *secret_phrase = byte_serialize(secret_phrase.as_bytes()).collect();
         }
 
         let headers = Client::submit_nonce_headers(proxy_details.clone(), total_size_gb, additional_headers);
 
-        let client = InnerClient::builder()
+        // `reqwest::Client` already pools connections behind an internal
+        // `Arc`, so cloning `Client` is cheap and reuses this same pool --
+        // callers should hold onto one `Client` (or a clone of it) rather
+        // than rebuilding one per request. Keep-alive is on by default;
+        // pin down the idle-pool bounds explicitly so a burst of
+        // submissions at round start reuses warm connections instead of
+        // renegotiating TCP/TLS for each one.
+        let inner = InnerClient::builder()
             .timeout(Duration::from_millis(timeout))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .pool_max_idle_per_host(4)
+            .tcp_keepalive(Duration::from_secs(60))
             .build()
             .unwrap();
 
-        Self {
-            inner: client,
+        let client = Self {
+            inner,
             account_id_to_secret_phrase: Arc::new(secret_phrases),
-            base_uri,
+            endpoints: Arc::new(endpoints),
+            active_endpoint: Arc::new(AtomicUsize::new(0)),
+            consecutive_failures: Arc::new(AtomicU32::new(0)),
             total_size_gb,
             proxy_details,
             headers: Arc::new(Mutex::new(headers)),
+            metrics: Arc::new(Metrics::new()),
+        };
+
+        if client.endpoints.len() > 1 {
+            client.spawn_health_check();
         }
+
+        client
     }
 
-    pub fn uri_for(&self, path: &str) -> Url {
-        let mut url = self.base_uri.clone();
+    /// Probes the active endpoint (and the primary, if a backup is
+    /// currently active) on a fixed interval, failing over after
+    /// `MAX_CONSECUTIVE_FAILURES` and failing back to the primary on the
+    /// first probe that finds it reachable again.
+    fn spawn_health_check(&self) {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let active = client.active_endpoint.load(AtomicOrdering::SeqCst);
+                if active != 0 && client.probe(0).await {
+                    info!(
+                        "connectivity: primary endpoint {} reachable again, failing back",
+                        client.endpoints[0]
+                    );
+                    client.active_endpoint.store(0, AtomicOrdering::SeqCst);
+                    client.consecutive_failures.store(0, AtomicOrdering::SeqCst);
+                    continue;
+                }
+
+                if client.probe(active).await {
+                    client.consecutive_failures.store(0, AtomicOrdering::SeqCst);
+                    continue;
+                }
+
+                let failures = client.consecutive_failures.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+                if failures >= MAX_CONSECUTIVE_FAILURES {
+                    let len = client.endpoints.len();
+                    let mut next = (active + 1) % len;
+                    while next != active && !client.probe(next).await {
+                        next = (next + 1) % len;
+                    }
+                    if next == active {
+                        warn!(
+                            "connectivity: endpoint {} down after {} consecutive failures, no other endpoint reachable",
+                            client.endpoints[active], failures
+                        );
+                        continue;
+                    }
+                    warn!(
+                        "connectivity: endpoint {} down after {} consecutive failures, failing over to {}",
+                        client.endpoints[active], failures, client.endpoints[next]
+                    );
+                    client.active_endpoint.store(next, AtomicOrdering::SeqCst);
+                    client.consecutive_failures.store(0, AtomicOrdering::SeqCst);
+                }
+            }
+        });
+    }
+
+    /// Lightweight reachability check against `endpoints[idx]`.
+    async fn probe(&self, idx: usize) -> bool {
+        self.inner
+            .get(Self::endpoint_uri(&self.endpoints[idx], "burst"))
+            .query(&GetMiningInfoRequest {
+                request_type: "getMiningInfo",
+            })
+            .send()
+            .await
+            .map(|res| res.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// The endpoint `uri_for` currently resolves requests against.
+    pub fn active_endpoint(&self) -> Url {
+        self.endpoints[self.active_endpoint.load(AtomicOrdering::SeqCst)].clone()
+    }
+
+    /// Whether no health probe of the active endpoint has failed since it
+    /// last answered a request or probe successfully (or was switched to).
+    /// Failed `get_mining_info`/`submit_nonce` requests are not counted.
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures.load(AtomicOrdering::SeqCst) == 0
+    }
+
+    /// Latency/counter metrics for this client's `get_mining_info` and
+    /// `submit_nonce` calls, for a caller (e.g. a Prometheus text endpoint)
+    /// to render.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    fn endpoint_uri(endpoint: &Url, path: &str) -> Url {
+        let mut url = endpoint.clone();
         url.path_segments_mut()
             .expect("cannot be base")
             .pop_if_empty()
@@ -142,6 +318,11 @@ impl Client {
         url
     }
 
+    pub fn uri_for(&self, path: &str) -> Url {
+        let idx = self.active_endpoint.load(AtomicOrdering::SeqCst);
+        Self::endpoint_uri(&self.endpoints[idx], path)
+    }
+
     #[cfg(feature = "async_io")]
     pub async fn update_capacity(&mut self, total_size_gb: usize) {
         self.total_size_gb = total_size_gb;
@@ -161,6 +342,13 @@ impl Client {
     }
 
     pub async fn get_mining_info(&self) -> Result<MiningInfoResponse, FetchError> {
+        let started = Instant::now();
+        let result = self.get_mining_info_inner().await;
+        self.metrics.record_get_mining_info(started.elapsed(), &result);
+        result
+    }
+
+    async fn get_mining_info_inner(&self) -> Result<MiningInfoResponse, FetchError> {
         #[cfg(feature = "async_io")]
         let headers = { self.headers.lock().await.clone() };
         #[cfg(not(feature = "async_io"))]
@@ -178,12 +366,64 @@ impl Client {
             .bytes()
             .await?;
 
-        parse_json_result(&res).map_err(FetchError::from)
+        let result = parse_json_result(&res).map_err(FetchError::from);
+        if result.is_ok() {
+            self.consecutive_failures.store(0, AtomicOrdering::SeqCst);
+        }
+        result
+    }
+
+    /// Long-poll variant of `get_mining_info`: tells the pool/node the last
+    /// generation signature we saw so it can hold the connection open and
+    /// answer only once the block actually changes, instead of us polling it
+    /// on a fixed `Interval`. Not every pool supports this — callers should
+    /// treat an immediate response with an unchanged generation signature as
+    /// "no long-poll support" and fall back to `get_mining_info`.
+    pub async fn get_mining_info_longpoll(
+        &self,
+        last_generation_signature: &str,
+        wait_timeout: Duration,
+    ) -> Result<MiningInfoResponse, FetchError> {
+        #[cfg(feature = "async_io")]
+        let headers = { self.headers.lock().await.clone() };
+        #[cfg(not(feature = "async_io"))]
+        let headers = { self.headers.lock().unwrap().clone() };
+
+        let res = self
+            .inner
+            .get(self.uri_for("burst"))
+            .headers(headers)
+            .query(&[
+                ("requestType", "getMiningInfo"),
+                ("lastGenerationSignature", last_generation_signature),
+            ])
+            .timeout(wait_timeout)
+            .send()
+            .await?
+            .bytes()
+            .await?;
+
+        let result = parse_json_result(&res).map_err(FetchError::from);
+        if result.is_ok() {
+            self.consecutive_failures.store(0, AtomicOrdering::SeqCst);
+        }
+        result
     }
 
     pub async fn submit_nonce(
         &self,
         submission_data: &SubmissionParameters,
+    ) -> Result<SubmitNonceResponse, FetchError> {
+        let started = Instant::now();
+        let endpoint = self.active_endpoint().to_string();
+        let result = self.submit_nonce_inner(submission_data).await;
+        self.metrics.record_submit(&endpoint, started.elapsed(), &result);
+        result
+    }
+
+    async fn submit_nonce_inner(
+        &self,
+        submission_data: &SubmissionParameters,
     ) -> Result<SubmitNonceResponse, FetchError> {
         let empty = "".to_owned();
         let secret_phrase = self
@@ -224,7 +464,11 @@ impl Client {
             .bytes()
             .await?;
 
-        parse_json_result(&res).map_err(FetchError::from)
+        let result = parse_json_result(&res).map_err(FetchError::from);
+        if result.is_ok() {
+            self.consecutive_failures.store(0, AtomicOrdering::SeqCst);
+        }
+        result
     }
 }
 
@@ -245,6 +489,8 @@ mod tests {
             deadline_unadjusted: 7123,
             deadline: 1193,
             gen_sig: [0; 32],
+            attempt: 0,
+            first_seen: Instant::now(),
         };
 
         let mut submit_params_2 = submit_params_1.clone();
@@ -292,6 +538,8 @@ mod tests {
             deadline_unadjusted: 7123,
             deadline: 1193,
             gen_sig: [0; 32],
+            attempt: 0,
+            first_seen: Instant::now(),
         };
 
         let result = client.submit_nonce(&submission).await;
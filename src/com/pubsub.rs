@@ -0,0 +1,50 @@
+//! Optional WebSocket push notifications for new blocks, as an alternative
+//! to polling `Client::get_mining_info`/`get_mining_info_longpoll`. A pool
+//! that exposes a subscription endpoint can push the next
+//! `MiningInfoResponse` the instant it mines a block, instead of the miner
+//! finding out on its next poll tick. Connection loss or an unsupported pool
+//! just surfaces as a `FetchError` like any other failed fetch; callers are
+//! expected to fall back to the fixed-interval/long-poll tasks, which keep
+//! running unconditionally anyway.
+
+use crate::com::api::{FetchError, MiningInfoResponse};
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+/// A live subscription to a pool's block-notification WebSocket endpoint.
+pub struct PubsubClient {
+    socket: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+}
+
+impl PubsubClient {
+    /// Opens the subscription. `uri` is the pool's WebSocket endpoint, e.g.
+    /// `wss://pool.example.com/ws`.
+    pub async fn connect(uri: &Url) -> Result<Self, FetchError> {
+        let (socket, _response) = connect_async(uri.as_str())
+            .await
+            .map_err(|e| FetchError::Http(e.to_string()))?;
+        Ok(PubsubClient { socket })
+    }
+
+    /// Waits for and parses the next push notification. Returns an error
+    /// (including on a clean server-initiated close) so the caller can
+    /// reconnect or fall back to polling.
+    pub async fn next_mining_info(&mut self) -> Result<MiningInfoResponse, FetchError> {
+        loop {
+            match self.socket.next().await {
+                Some(Ok(Message::Text(text))) => {
+                    return serde_json::from_str(&text)
+                        .map_err(|e| FetchError::Http(e.to_string()));
+                }
+                Some(Ok(Message::Ping(payload))) => {
+                    let _ = self.socket.send(Message::Pong(payload)).await;
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(FetchError::Http(e.to_string())),
+                None => return Err(FetchError::Http("pubsub connection closed".into())),
+            }
+        }
+    }
+}
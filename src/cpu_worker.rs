@@ -1,26 +1,37 @@
+// `build.rs` refuses to configure a big-endian target, so this cfg is always
+// set for any build that reaches this point; asserted here rather than relied
+// on implicitly so a future build.rs regression fails loudly at compile time.
+#[cfg(not(shabal_little_endian))]
+compile_error!("signum-miner requires a little-endian target (see build.rs::check_endianness)");
+
 use crate::miner::{Buffer, NonceData};
-#[cfg(any(
-    test,
-    not(any(
-        feature = "simd_avx512f",
-        feature = "simd_avx2",
-        feature = "simd_avx",
-        feature = "simd_sse2",
-        feature = "neon",
-    ))
-))]
 use crate::poc_hashing::find_best_deadline_rust;
 use crate::reader::ReadReply;
+use crate::worker::{WorkerCommand, WorkerRegistry, WorkerState};
 use crossbeam_channel::{Receiver, Sender};
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::sync::OnceLock;
 use std::u64;
+use tokio::runtime::Handle;
 use tokio::sync::mpsc::Sender as TokioSender;
 
-#[cfg(any(feature = "simd", feature = "neon"))]
+#[cfg(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64",
+    target_arch = "wasm32"
+))]
 use libc::c_void;
 
-#[cfg(feature = "simd_avx512f")]
+// Each `have_<variant>` cfg is emitted by `build.rs` only when the C compiler
+// actually accepted that variant's flags, so a toolchain that rejects e.g.
+// `-mavx512f` simply never sees this symbol instead of failing to link.
+#[cfg(have_shabal_avx512f)]
 extern "C" {
-    pub fn find_best_deadline_avx512f(
+    fn find_best_deadline_avx512f(
         scoops: *mut c_void,
         nonce_count: u64,
         gensig: *const c_void,
@@ -28,10 +39,9 @@ extern "C" {
         best_offset: *mut u64,
     );
 }
-
-#[cfg(feature = "simd_avx2")]
+#[cfg(have_shabal_avx2)]
 extern "C" {
-    pub fn find_best_deadline_avx2(
+    fn find_best_deadline_avx2(
         scoops: *mut c_void,
         nonce_count: u64,
         gensig: *const c_void,
@@ -39,10 +49,19 @@ extern "C" {
         best_offset: *mut u64,
     );
 }
-
-#[cfg(feature = "simd_avx")]
+#[cfg(have_shabal_avx)]
+extern "C" {
+    fn find_best_deadline_avx(
+        scoops: *mut c_void,
+        nonce_count: u64,
+        gensig: *const c_void,
+        best_deadline: *mut u64,
+        best_offset: *mut u64,
+    );
+}
+#[cfg(have_shabal_sse2)]
 extern "C" {
-    pub fn find_best_deadline_avx(
+    fn find_best_deadline_sse2(
         scoops: *mut c_void,
         nonce_count: u64,
         gensig: *const c_void,
@@ -51,9 +70,9 @@ extern "C" {
     );
 }
 
-#[cfg(feature = "simd_sse2")]
+#[cfg(have_shabal_neon)]
 extern "C" {
-    pub fn find_best_deadline_sse2(
+    fn find_best_deadline_neon(
         scoops: *mut c_void,
         nonce_count: u64,
         gensig: *const c_void,
@@ -62,9 +81,9 @@ extern "C" {
     );
 }
 
-#[cfg(feature = "neon")]
+#[cfg(have_shabal_wasm32_simd128)]
 extern "C" {
-    pub fn find_best_deadline_neon(
+    fn find_best_deadline_wasm32_simd128(
         scoops: *mut c_void,
         nonce_count: u64,
         gensig: *const c_void,
@@ -73,24 +92,163 @@ extern "C" {
     );
 }
 
+/// The Shabal kernel picked for this process, resolved once at first use.
+///
+/// Rather than baking a single SIMD variant into the binary at compile time, we
+/// probe the running CPU with `is_x86_feature_detected!` and remember the best
+/// match, following the same one-binary-fits-all-CPUs approach BLAKE3 uses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum SimdVariant {
+    Avx512f,
+    Avx2,
+    Avx,
+    Sse2,
+    Neon,
+    Wasm32Simd128,
+    Scalar,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn detect_simd_variant() -> SimdVariant {
+    // `SIGNUM_FORCE_SIMD` lets operators pin a variant, e.g. to dodge the clock
+    // throttling AVX-512 can trigger on this memory-bound workload.
+    if let Ok(forced) = env::var("SIGNUM_FORCE_SIMD") {
+        match forced.to_lowercase().as_str() {
+            "avx512f" => return SimdVariant::Avx512f,
+            "avx2" => return SimdVariant::Avx2,
+            "avx" => return SimdVariant::Avx,
+            "sse2" => return SimdVariant::Sse2,
+            "scalar" | "none" => return SimdVariant::Scalar,
+            other => warn!("SIGNUM_FORCE_SIMD: unknown variant '{}', ignoring", other),
+        }
+    }
+
+    if cfg!(have_shabal_avx512f) && std::is_x86_feature_detected!("avx512f") {
+        SimdVariant::Avx512f
+    } else if cfg!(have_shabal_avx2) && std::is_x86_feature_detected!("avx2") {
+        SimdVariant::Avx2
+    } else if cfg!(have_shabal_avx) && std::is_x86_feature_detected!("avx") {
+        SimdVariant::Avx
+    } else if cfg!(have_shabal_sse2) && std::is_x86_feature_detected!("sse2") {
+        SimdVariant::Sse2
+    } else {
+        SimdVariant::Scalar
+    }
+}
+
+#[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+fn detect_simd_variant() -> SimdVariant {
+    #[cfg(target_arch = "arm")]
+    let neon = is_arm_feature_detected!("neon");
+    #[cfg(target_arch = "aarch64")]
+    let neon = true;
+
+    if cfg!(have_shabal_neon) && neon {
+        SimdVariant::Neon
+    } else {
+        SimdVariant::Scalar
+    }
+}
+
+// Unlike the x86 variants, wasm32 does not participate in runtime feature
+// detection: if the `wasm32_simd` feature was on at build time, `simd128` is
+// simply assumed to be present in the target wasm runtime.
+#[cfg(target_arch = "wasm32")]
+fn detect_simd_variant() -> SimdVariant {
+    if cfg!(have_shabal_wasm32_simd128) {
+        SimdVariant::Wasm32Simd128
+    } else {
+        SimdVariant::Scalar
+    }
+}
+
+#[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64",
+    target_arch = "wasm32"
+)))]
+fn detect_simd_variant() -> SimdVariant {
+    SimdVariant::Scalar
+}
+
+static SIMD_VARIANT: OnceLock<SimdVariant> = OnceLock::new();
+
+/// Resolves (once) and returns the Shabal kernel this process will use.
+pub fn simd_variant() -> SimdVariant {
+    *SIMD_VARIANT.get_or_init(|| {
+        let variant = detect_simd_variant();
+        info!("SIMD extensions: {:?}", variant);
+        variant
+    })
+}
+
+/// Registers itself as `"cpu-worker"` with `registry` so its status shows up
+/// in the `SIGUSR1` report and it can be paused/cancelled through the
+/// control socket like the async workers. This runs on a bare OS thread
+/// fed by a blocking `crossbeam_channel::Receiver`, not a tokio task, so it
+/// bridges into the (async) registry via `executor.block_on`. `Pause`
+/// mirrors the existing stale-block skip path in [`hash`]: incoming reads
+/// keep draining (so the reader pipeline upstream never blocks) but nothing
+/// gets dispatched to the thread pool for hashing, and `finished` rounds
+/// still get their completion marker so round bookkeeping isn't stuck
+/// waiting on a task that will never report in.
 pub fn create_cpu_worker_task(
     benchmark: bool,
     thread_pool: rayon::ThreadPool,
     rx_read_replies: Receiver<ReadReply>,
     tx_empty_buffers: Sender<Box<dyn Buffer + Send>>,
     tx_nonce_data: TokioSender<NonceData>,
+    block_generation: Arc<AtomicU64>,
+    registry: WorkerRegistry,
+    executor: Handle,
 ) -> impl FnOnce() + Send + 'static {
     move || {
+        let (worker, mut cmd_rx) = executor.block_on(registry.register("cpu-worker"));
+        let mut paused = false;
+
         for read_reply in rx_read_replies {
+            while let Ok(cmd) = cmd_rx.try_recv() {
+                match cmd {
+                    WorkerCommand::Start => paused = false,
+                    WorkerCommand::Pause => paused = true,
+                    WorkerCommand::Cancel => {
+                        executor.block_on(worker.report(WorkerState::Dead));
+                        return;
+                    }
+                }
+            }
+
+            if paused {
+                if read_reply.info.finished {
+                    let _ = tx_nonce_data.blocking_send(NonceData {
+                        height: read_reply.info.height,
+                        block: read_reply.info.block,
+                        base_target: read_reply.info.base_target,
+                        deadline: u64::MAX,
+                        nonce: 0,
+                        reader_task_processed: read_reply.info.finished,
+                        account_id: read_reply.info.account_id,
+                    });
+                }
+                let _ = tx_empty_buffers.send(read_reply.buffer);
+                executor.block_on(worker.report(WorkerState::Idle));
+                continue;
+            }
+
             let task = hash(
                 read_reply,
                 tx_empty_buffers.clone(),
                 tx_nonce_data.clone(),
                 benchmark,
+                block_generation.clone(),
             );
 
             thread_pool.spawn(task);
+            executor.block_on(worker.report(WorkerState::Active));
         }
+        executor.block_on(worker.report(WorkerState::Dead));
     }
 }
 
@@ -99,10 +257,33 @@ pub fn hash(
     tx_empty_buffers: Sender<Box<dyn Buffer + Send>>,
     tx_nonce_data: TokioSender<NonceData>,
     benchmark: bool,
+    block_generation: Arc<AtomicU64>,
 ) -> impl FnOnce() + Send + 'static {
     move || {
         let mut buffer = read_reply.buffer;
 
+        // Work restart: a new block may have started while this task was
+        // sitting in the reader's queue. Checking the shared counter here
+        // avoids spending a full Shabal pass on a scoop nobody will submit
+        // deadlines for anymore; the `finished` marker below still needs to
+        // go out so `Miner::run`'s round-completion bookkeeping isn't stuck
+        // waiting on a task that silently vanished.
+        if read_reply.info.block < block_generation.load(Ordering::SeqCst) {
+            if read_reply.info.finished {
+                let _ = tx_nonce_data.blocking_send(NonceData {
+                    height: read_reply.info.height,
+                    block: read_reply.info.block,
+                    base_target: read_reply.info.base_target,
+                    deadline: u64::MAX,
+                    nonce: 0,
+                    reader_task_processed: read_reply.info.finished,
+                    account_id: read_reply.info.account_id,
+                });
+            }
+            let _ = tx_empty_buffers.send(buffer);
+            return;
+        }
+
         if read_reply.info.len == 0 || benchmark {
             if read_reply.info.finished {
                 let deadline = u64::MAX;
@@ -135,92 +316,76 @@ pub fn hash(
 #[cfg(not(feature = "async_io"))]
         let bs = bs.lock().unwrap();
 
-        #[cfg(feature = "simd_avx512f")]
-        unsafe {
-            find_best_deadline_avx512f(
-                bs.as_ptr() as *mut c_void,
-                (read_reply.info.len as u64) / 64,
-                read_reply.info.gensig.as_ptr() as *const c_void,
-                &mut deadline,
-                &mut offset,
-            );
-        }
-
-        #[cfg(feature = "simd_avx2")]
-        unsafe {
-            find_best_deadline_avx2(
-                bs.as_ptr() as *mut c_void,
-                (read_reply.info.len as u64) / 64,
-                read_reply.info.gensig.as_ptr() as *const c_void,
-                &mut deadline,
-                &mut offset,
-            );
-        }
-
-        #[cfg(feature = "simd_avx")]
-        unsafe {
-            find_best_deadline_avx(
-                bs.as_ptr() as *mut c_void,
-                (read_reply.info.len as u64) / 64,
-                read_reply.info.gensig.as_ptr() as *const c_void,
-                &mut deadline,
-                &mut offset,
-            );
-        }
-
-        #[cfg(feature = "simd_sse2")]
-        unsafe {
-            find_best_deadline_sse2(
-                bs.as_ptr() as *mut c_void,
-                (read_reply.info.len as u64) / 64,
-                read_reply.info.gensig.as_ptr() as *const c_void,
-                &mut deadline,
-                &mut offset,
-            );
-        }
-
-        #[cfg(feature = "neon")]
-        unsafe {
-            #[cfg(target_arch = "arm")]
-            let neon = is_arm_feature_detected!("neon");
-            #[cfg(target_arch = "aarch64")]
-            let neon = true;
-            if neon {
+        let nonce_count = (read_reply.info.len as u64) / 64;
+        match simd_variant() {
+            #[cfg(have_shabal_avx512f)]
+            SimdVariant::Avx512f => unsafe {
+                find_best_deadline_avx512f(
+                    bs.as_ptr() as *mut c_void,
+                    nonce_count,
+                    read_reply.info.gensig.as_ptr() as *const c_void,
+                    &mut deadline,
+                    &mut offset,
+                );
+            },
+            #[cfg(have_shabal_avx2)]
+            SimdVariant::Avx2 => unsafe {
+                find_best_deadline_avx2(
+                    bs.as_ptr() as *mut c_void,
+                    nonce_count,
+                    read_reply.info.gensig.as_ptr() as *const c_void,
+                    &mut deadline,
+                    &mut offset,
+                );
+            },
+            #[cfg(have_shabal_avx)]
+            SimdVariant::Avx => unsafe {
+                find_best_deadline_avx(
+                    bs.as_ptr() as *mut c_void,
+                    nonce_count,
+                    read_reply.info.gensig.as_ptr() as *const c_void,
+                    &mut deadline,
+                    &mut offset,
+                );
+            },
+            #[cfg(have_shabal_sse2)]
+            SimdVariant::Sse2 => unsafe {
+                find_best_deadline_sse2(
+                    bs.as_ptr() as *mut c_void,
+                    nonce_count,
+                    read_reply.info.gensig.as_ptr() as *const c_void,
+                    &mut deadline,
+                    &mut offset,
+                );
+            },
+            #[cfg(have_shabal_neon)]
+            SimdVariant::Neon => unsafe {
                 find_best_deadline_neon(
                     bs.as_ptr() as *mut c_void,
-                    (read_reply.info.len as u64) / 64,
+                    nonce_count,
                     read_reply.info.gensig.as_ptr() as *const c_void,
                     &mut deadline,
                     &mut offset,
                 );
-            } else {
-                let result = find_best_deadline_rust(
-                    &bs,
-                    (read_reply.info.len as u64) / 64,
-                    &*read_reply.info.gensig,
+            },
+            #[cfg(have_shabal_wasm32_simd128)]
+            SimdVariant::Wasm32Simd128 => unsafe {
+                find_best_deadline_wasm32_simd128(
+                    bs.as_ptr() as *mut c_void,
+                    nonce_count,
+                    read_reply.info.gensig.as_ptr() as *const c_void,
+                    &mut deadline,
+                    &mut offset,
                 );
+            },
+            _ => {
+                let result =
+                    find_best_deadline_rust(&bs, nonce_count, &*read_reply.info.gensig);
                 deadline = result.0;
                 offset = result.1;
             }
         }
 
-        #[cfg(not(any(
-            feature = "simd_avx512f",
-            feature = "simd_avx2",
-            feature = "simd_avx",
-            feature = "simd_sse2",
-            feature = "neon"
-        )))]
-        {
-            let result = find_best_deadline_rust(
-                &bs,
-                (read_reply.info.len as u64) / 64,
-                &*read_reply.info.gensig,
-            );
-            deadline = result.0;
-            offset = result.1;
-        }
-
         let _ = tx_nonce_data.blocking_send(NonceData {
             height: read_reply.info.height,
             block: read_reply.info.block,
@@ -2,7 +2,9 @@
 //!
 //! An element that is enqueued will be yielded instantly if it is a new element.
 //! Otherwise it will be delayed according to the number of times that it has been enqueued
-//! consecutively.
+//! consecutively: `base * 2^(attempts-1)`, capped at `max_delay`, and optionally
+//! randomized (full jitter) so many items backing off in lockstep don't all
+//! resubmit at the exact same instant.
 //! New items will replace old items and start with a delay of 0.
 
 use std::{
@@ -15,8 +17,52 @@ use futures_core::Stream;
 use futures_core::Future;
 use futures_util::stream::StreamExt;
 use pin_project::pin_project;
+use rand::Rng;
 use tokio::time::{self, Duration, Instant, Sleep};
 
+/// Backoff tunables for a [`PrioRetry`] stream.
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    /// Delay for the first retry of a consecutively-resubmitted item.
+    pub base_delay: Duration,
+    /// Upper bound the doubling delay is capped at.
+    pub max_delay: Duration,
+    /// If set, the actual sleep is picked uniformly from `[0, computed_delay]`
+    /// instead of using `computed_delay` directly.
+    pub full_jitter: bool,
+}
+
+impl Backoff {
+    pub fn new(base_delay: Duration, max_delay: Duration, full_jitter: bool) -> Self {
+        Self {
+            base_delay,
+            max_delay,
+            full_jitter,
+        }
+    }
+
+    /// Delay for the `attempts`-th consecutive occurrence of the same item
+    /// (`attempts == 0` meaning "brand new", which is always instant).
+    fn delay_for(&self, attempts: u32) -> Duration {
+        if attempts == 0 {
+            return Duration::ZERO;
+        }
+        let shift = (attempts - 1).min(31);
+        let computed = self
+            .base_delay
+            .checked_mul(1u32 << shift)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        if self.full_jitter && computed > Duration::ZERO {
+            let jittered_nanos = rand::thread_rng().gen_range(0..=computed.as_nanos() as u64);
+            Duration::from_nanos(jittered_nanos)
+        } else {
+            computed
+        }
+    }
+}
+
 #[pin_project]
 pub struct PrioRetry<S>
 where
@@ -27,8 +73,8 @@ where
     stream: S,
     #[pin]
     delay: Sleep,
-    delay_duration: Duration,
-    delayed_item: Option<DelayedItem<S::Item>>,
+    backoff: Backoff,
+    armed: Option<DelayedItem<S::Item>>,
     buffer: BinaryHeap<S::Item>,
 }
 
@@ -37,12 +83,12 @@ where
     S: Stream,
     S::Item: Ord + Clone + Eq,
 {
-    pub fn new(stream: S, delay_duration: Duration) -> Self {
+    pub fn new(stream: S, backoff: Backoff) -> Self {
         Self {
             stream,
-            delay: time::sleep(delay_duration),
-            delay_duration,
-            delayed_item: None,
+            delay: time::sleep(Duration::ZERO),
+            backoff,
+            armed: None,
             buffer: BinaryHeap::new(),
         }
     }
@@ -61,17 +107,21 @@ where
         while let Poll::Ready(opt_item) = this.stream.as_mut().poll_next(cx) {
             match opt_item {
                 Some(new_item) => {
-                    if let Some(delayed_item) = this.delayed_item.as_ref() {
-                        if new_item <= delayed_item.item {
-                            this.buffer.push(new_item);
+                    if let Some(armed) = this.armed.as_mut() {
+                        if new_item == armed.item {
+                            armed.attempts += 1;
+                            let delay = this.backoff.delay_for(armed.attempts);
+                            this.delay.as_mut().reset(Instant::now() + delay);
+                        } else if new_item > armed.item {
+                            this.buffer.push(armed.item.clone());
+                            *this.armed = Some(DelayedItem::new(new_item));
+                            this.delay.as_mut().reset(Instant::now());
                         } else {
-                            this.buffer.push(delayed_item.item.clone());
-                            *this.delayed_item = Some(DelayedItem::new(new_item.clone(), *this.delay_duration));
-                            this.delay.as_mut().reset(Instant::now() + *this.delay_duration);
+                            this.buffer.push(new_item);
                         }
                     } else {
-                        *this.delayed_item = Some(DelayedItem::new(new_item.clone(), *this.delay_duration));
-                        this.delay.as_mut().reset(Instant::now() + *this.delay_duration);
+                        *this.armed = Some(DelayedItem::new(new_item));
+                        this.delay.as_mut().reset(Instant::now());
                     }
                 }
                 None => break,
@@ -79,8 +129,13 @@ where
         }
 
         if this.delay.as_mut().poll(cx).is_ready() {
-            if let Some(delayed_item) = this.delayed_item.take() {
-                return Poll::Ready(Some(delayed_item.item));
+            if let Some(armed) = this.armed.take() {
+                if let Some(next) = this.buffer.pop() {
+                    *this.armed = Some(DelayedItem::new(next));
+                    this.delay.as_mut().reset(Instant::now());
+                    cx.waker().wake_by_ref();
+                }
+                return Poll::Ready(Some(armed.item));
             }
         }
 
@@ -88,18 +143,16 @@ where
     }
 }
 
-#[pin_project]
+/// The item currently "armed": its resubmission timer is the one driving
+/// `PrioRetry::delay`, and `attempts` counts how many times in a row it has
+/// been re-enqueued without being displaced by a strictly better item.
 struct DelayedItem<T> {
     item: T,
-    #[pin]
-    delay: Sleep,
+    attempts: u32,
 }
 
 impl<T> DelayedItem<T> {
-    fn new(item: T, duration: Duration) -> Self {
-        Self {
-            item,
-            delay: time::sleep(duration),
-        }
+    fn new(item: T) -> Self {
+        Self { item, attempts: 0 }
     }
 }
@@ -0,0 +1,147 @@
+//! Compact fixed-bucket logarithmic histogram for latency/value distributions
+//! (deadlines, round times, submit round-trip times) that are too skewed for
+//! a single min/max/average to say much. Buckets are power-of-two ranges
+//! refined into `SUB_BUCKETS` linear steps per octave, so relative precision
+//! stays roughly constant across the whole value range while keeping the
+//! bucket count (and thus memory/record cost) fixed regardless of how large
+//! a value gets.
+//!
+//! Buckets are plain atomics rather than a lock-guarded `Vec`, so `record`
+//! can be called from the hot submission/poll path by many threads at once
+//! without contending on a mutex; a read (`snapshot`) just sums the current
+//! counts, which only needs to be approximately consistent.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// log2(sub-buckets per octave); 3 -> 8 sub-buckets per power of two.
+const SUB_BUCKET_BITS: u32 = 3;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+/// Values below this fall into their own exact-value bucket rather than the
+/// octave scheme, which would otherwise need a negative exponent for them.
+const LINEAR_CUTOFF: u64 = SUB_BUCKETS;
+/// One bucket per exact value below `LINEAR_CUTOFF`, plus `SUB_BUCKETS`
+/// buckets for every octave up to u64's 64 bits.
+const NUM_BUCKETS: usize = (LINEAR_CUTOFF + ((64 + 1) << SUB_BUCKET_BITS)) as usize;
+
+fn bucket_index(v: u64) -> usize {
+    if v < LINEAR_CUTOFF {
+        return v as usize;
+    }
+    let exponent = 63 - v.leading_zeros();
+    let mantissa = (v >> (exponent - SUB_BUCKET_BITS)) & (SUB_BUCKETS - 1);
+    ((exponent << SUB_BUCKET_BITS) as u64 | mantissa) as usize
+}
+
+fn bucket_lower_bound(idx: usize) -> u64 {
+    let idx = idx as u64;
+    if idx < LINEAR_CUTOFF {
+        return idx;
+    }
+    let exponent = idx >> SUB_BUCKET_BITS;
+    let mantissa = idx & (SUB_BUCKETS - 1);
+    (1u64 << exponent) | (mantissa << (exponent - SUB_BUCKET_BITS))
+}
+
+/// Point-in-time read of a [`Histogram`]: the percentiles operators actually
+/// want to see, without handing out the raw bucket array.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+/// A single logarithmic histogram. Cheap to record into (two atomic
+/// increments, no allocation, no lock) so it can sit on the hot path of every
+/// nonce processed, every submission sent, or every request a `Client` makes.
+#[derive(Debug)]
+pub struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Histogram {
+            buckets: (0..NUM_BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, value: u64) {
+        self.buckets[bucket_index(value)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.buckets.iter().for_each(|b| b.store(0, Ordering::Relaxed));
+        self.count.store(0, Ordering::Relaxed);
+    }
+
+    /// Walks cumulative bucket counts until reaching the target rank for
+    /// `p` (e.g. `p = 0.99` for p99), returning that bucket's lower bound.
+    fn percentile(&self, count: u64, p: f64) -> u64 {
+        if count == 0 {
+            return 0;
+        }
+        let target = ((count as f64) * p).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            if cumulative >= target {
+                return bucket_lower_bound(idx);
+            }
+        }
+        bucket_lower_bound(self.buckets.len() - 1)
+    }
+
+    fn max(&self) -> u64 {
+        self.buckets
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, c)| c.load(Ordering::Relaxed) > 0)
+            .map(|(idx, _)| bucket_lower_bound(idx))
+            .unwrap_or(0)
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let count = self.count.load(Ordering::Relaxed);
+        HistogramSnapshot {
+            count,
+            p50: self.percentile(count, 0.50),
+            p90: self.percentile(count, 0.90),
+            p99: self.percentile(count, 0.99),
+            max: self.max(),
+        }
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_and_reset() {
+        let h = Histogram::new();
+        for v in 1..=100u64 {
+            h.record(v);
+        }
+        let snap = h.snapshot();
+        assert_eq!(snap.count, 100);
+        assert!(snap.p50 <= 55 && snap.p50 >= 40);
+        assert!(snap.p99 <= 100 && snap.p99 >= 90);
+        assert_eq!(snap.max, bucket_lower_bound(bucket_index(100)));
+
+        h.reset();
+        assert_eq!(h.snapshot().count, 0);
+    }
+}
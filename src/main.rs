@@ -4,22 +4,26 @@
 #[macro_use]
 extern crate serde_derive;
 #[macro_use]
-extern crate cfg_if;
-#[macro_use]
 extern crate log;
 
+mod affinity;
 mod com;
 mod config;
 mod cpu_worker;
 mod future;
+mod histogram;
 mod logger;
+mod metrics;
 mod miner;
 mod plot;
 mod poc_hashing;
+mod proxy;
 mod reader;
 mod requests;
+mod scrub;
 mod shabal256;
 mod utils;
+mod worker;
 
 #[cfg(feature = "opencl")]
 mod gpu_worker;
@@ -30,60 +34,12 @@ mod ocl;
 
 use crate::config::load_cfg;
 use crate::miner::Miner;
+use crate::requests::RequestHandler;
 use clap::{Arg, Command};
 use std::process;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
 
-cfg_if! {
-    if #[cfg(feature = "simd_avx512f")] {
-        extern "C" {
-            pub fn init_shabal_avx512f();
-        }
-
-        fn init_cpu_extensions() {
-            info!("SIMD extensions: AVX512F");
-            unsafe { init_shabal_avx512f(); }
-        }
-    } else if #[cfg(feature = "simd_avx2")] {
-        extern "C" {
-            pub fn init_shabal_avx2();
-        }
-
-        fn init_cpu_extensions() {
-            info!("SIMD extensions: AVX2");
-            unsafe { init_shabal_avx2(); }
-        }
-    } else if #[cfg(feature = "simd_avx")] {
-        extern "C" {
-            pub fn init_shabal_avx();
-        }
-
-        fn init_cpu_extensions() {
-            info!("SIMD extensions: AVX");
-            unsafe { init_shabal_avx(); }
-        }
-    } else if #[cfg(feature = "simd_sse2")] {
-        extern "C" {
-            pub fn init_shabal_sse2();
-        }
-
-        fn init_cpu_extensions() {
-            info!("SIMD extensions: SSE2");
-            unsafe { init_shabal_sse2(); }
-        }
-    }  else if #[cfg(feature = "neon")] {
-         extern "C" {
-            pub fn init_shabal_neon();
-        }
-        fn init_cpu_extensions() {
-            info!("SIMD extensions: neon");
-            unsafe { init_shabal_neon();}
-        }
-    } else {
-        fn init_cpu_extensions() {
-            info!("SIMD extensions: none");
-        }
-    }
-}
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 fn print_simd_support() {
     println!("SIMD support check:");
@@ -161,19 +117,71 @@ async fn main() {
         process::exit(0);
     }
 
-    #[cfg(any(
-        feature = "simd_avx512f",
-        feature = "simd_avx2",
-        feature = "simd_avx",
-        feature = "simd_sse2",
-        feature = "neon"
-    ))]
-    init_cpu_extensions();
+    // Resolve (and log) the Shabal kernel this process will use. Picked once from
+    // the running CPU's feature set rather than from compile-time flags, so a
+    // single binary adapts to whatever machine it lands on.
+    cpu_worker::simd_variant();
 
     #[cfg(feature = "opencl")]
     ocl::gpu_info(&cfg_loaded);
 
     let handle = tokio::runtime::Handle::current();
-    let miner = Miner::new(cfg_loaded, handle);
+    let miner = Miner::new(cfg_loaded, handle.clone());
+
+    // Lets operators add or drain a pool (or point at a different one) by
+    // editing the config file and sending SIGHUP, instead of restarting the
+    // process and losing whatever submissions were in flight.
+    #[cfg(unix)]
+    {
+        let request_handler = miner.request_handler();
+        #[cfg(feature = "async_io")]
+        let total_size_gb = miner.total_size_gb().await;
+        #[cfg(not(feature = "async_io"))]
+        let total_size_gb = miner.total_size_gb();
+        let config_path = config.to_owned();
+        tokio::spawn(async move {
+            let mut sighup = match signal(SignalKind::hangup()) {
+                Ok(sighup) => sighup,
+                Err(e) => {
+                    error!("could not install SIGHUP handler: {}", e);
+                    return;
+                }
+            };
+            loop {
+                sighup.recv().await;
+                info!("SIGHUP received, reloading pool list from {}", config_path);
+                let cfg = load_cfg(&config_path);
+                let pools = RequestHandler::resolve_pools(cfg.pools, cfg.url, cfg.account_id_to_secret_phrase);
+                let pool_count = pools.len();
+                #[cfg(feature = "async_io")]
+                RequestHandler::reload(
+                    &request_handler,
+                    pools,
+                    cfg.pool_strategy,
+                    cfg.timeout,
+                    total_size_gb,
+                    cfg.send_proxy_details,
+                    cfg.additional_headers,
+                    handle.clone(),
+                    cfg.max_submit_retries,
+                )
+                .await;
+                #[cfg(not(feature = "async_io"))]
+                RequestHandler::reload(
+                    &request_handler,
+                    pools,
+                    cfg.pool_strategy,
+                    cfg.timeout,
+                    total_size_gb,
+                    cfg.send_proxy_details,
+                    cfg.additional_headers,
+                    handle.clone(),
+                    cfg.max_submit_retries,
+                );
+                info!("pool list reloaded: {} pool(s)", pool_count);
+            }
+        });
+    }
+
     miner.run().await;
 }
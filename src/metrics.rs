@@ -0,0 +1,132 @@
+//! Lock-light latency/counter tracking for `Client::get_mining_info` and
+//! `Client::submit_nonce`, exposed as Prometheus-style text so it can be
+//! scraped without pulling in a full metrics crate. Submission latency is
+//! kept per endpoint (find-or-create under a `Mutex`, recorded lock-free
+//! afterwards) so a slow or flaky backup endpoint shows up on its own rather
+//! than being averaged away into the pool's overall number.
+
+use crate::com::api::FetchError;
+use crate::histogram::{Histogram, HistogramSnapshot};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Tracks successes and per-`FetchError`-variant failures for a single kind
+/// of request (`getMiningInfo` or `submitNonce`).
+#[derive(Debug, Default)]
+struct ResultCounters {
+    successes: AtomicU64,
+    errors_http: AtomicU64,
+    errors_pool: AtomicU64,
+}
+
+impl ResultCounters {
+    fn record<T>(&self, result: &Result<T, FetchError>) {
+        match result {
+            Ok(_) => self.successes.fetch_add(1, Ordering::Relaxed),
+            Err(FetchError::Http(_)) => self.errors_http.fetch_add(1, Ordering::Relaxed),
+            Err(FetchError::Pool(_)) => self.errors_pool.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    fn render(&self, name: &str, pool_label: &str, out: &mut String) {
+        for (result, count) in [
+            ("success", self.successes.load(Ordering::Relaxed)),
+            ("http_error", self.errors_http.load(Ordering::Relaxed)),
+            ("pool_error", self.errors_pool.load(Ordering::Relaxed)),
+        ] {
+            out.push_str(&format!(
+                "{name}{{pool=\"{pool_label}\",result=\"{result}\"}} {count}\n"
+            ));
+        }
+    }
+}
+
+fn render_histogram(name: &str, labels: &str, snapshot: &HistogramSnapshot, out: &mut String) {
+    out.push_str(&format!("{name}_count{{{labels}}} {}\n", snapshot.count));
+    for (quantile, value) in [("0.5", snapshot.p50), ("0.9", snapshot.p90), ("0.99", snapshot.p99)] {
+        out.push_str(&format!(
+            "{name}{{{labels},quantile=\"{quantile}\"}} {value}\n"
+        ));
+    }
+    out.push_str(&format!("{name}_max{{{labels}}} {}\n", snapshot.max));
+}
+
+/// Per-`Client` metrics, shared across clones of the same `Client` the same
+/// way `headers` and the endpoint-health atomics are.
+#[derive(Debug)]
+pub struct Metrics {
+    get_mining_info_latency: Histogram,
+    get_mining_info_results: ResultCounters,
+    submit_results: ResultCounters,
+    submit_latency_by_endpoint: Mutex<HashMap<String, Arc<Histogram>>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            get_mining_info_latency: Histogram::new(),
+            get_mining_info_results: ResultCounters::default(),
+            submit_results: ResultCounters::default(),
+            submit_latency_by_endpoint: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record_get_mining_info<T>(&self, elapsed: Duration, result: &Result<T, FetchError>) {
+        self.get_mining_info_latency.record(elapsed.as_millis() as u64);
+        self.get_mining_info_results.record(result);
+    }
+
+    pub fn record_submit<T>(&self, endpoint: &str, elapsed: Duration, result: &Result<T, FetchError>) {
+        self.submit_histogram_for(endpoint).record(elapsed.as_millis() as u64);
+        self.submit_results.record(result);
+    }
+
+    fn submit_histogram_for(&self, endpoint: &str) -> Arc<Histogram> {
+        let mut by_endpoint = self.submit_latency_by_endpoint.lock().unwrap();
+        by_endpoint
+            .entry(endpoint.to_owned())
+            .or_insert_with(|| Arc::new(Histogram::new()))
+            .clone()
+    }
+
+    /// Renders every tracked metric in Prometheus text exposition format,
+    /// tagging each line with `pool_label` so a scrape across several pools
+    /// (see `RequestHandler::render_metrics`) can tell them apart.
+    pub fn render_prometheus(&self, pool_label: &str) -> String {
+        let mut out = String::new();
+
+        render_histogram(
+            "signum_miner_get_mining_info_latency_ms",
+            &format!("pool=\"{pool_label}\""),
+            &self.get_mining_info_latency.snapshot(),
+            &mut out,
+        );
+        self.get_mining_info_results.render(
+            "signum_miner_get_mining_info_total",
+            pool_label,
+            &mut out,
+        );
+        self.submit_results
+            .render("signum_miner_submit_nonce_total", pool_label, &mut out);
+
+        let by_endpoint = self.submit_latency_by_endpoint.lock().unwrap();
+        for (endpoint, histogram) in by_endpoint.iter() {
+            render_histogram(
+                "signum_miner_submit_nonce_latency_ms",
+                &format!("pool=\"{pool_label}\",endpoint=\"{endpoint}\""),
+                &histogram.snapshot(),
+                &mut out,
+            );
+        }
+
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
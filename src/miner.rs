@@ -1,9 +1,12 @@
-use crate::com::api::MiningInfoResponse as MiningInfo;
+use crate::affinity::CoreAssigner;
+use crate::com::api::{FetchError, MiningInfoResponse as MiningInfo};
+use crate::com::pubsub::PubsubClient;
 use crate::config::Cfg;
 use crate::cpu_worker::create_cpu_worker_task;
 use crate::future::interval::Interval;
 #[cfg(feature = "opencl")]
 use crate::gpu_worker::create_gpu_worker_task;
+use crate::histogram::{Histogram, HistogramSnapshot};
 #[cfg(feature = "opencl")]
 use crate::gpu_worker_async::create_gpu_worker_task_async;
 #[cfg(feature = "opencl")]
@@ -12,12 +15,18 @@ use crate::ocl::GpuBuffer;
 use crate::ocl::GpuContext;
 use crate::plot::{Plot, SCOOP_SIZE};
 use crate::poc_hashing;
+use crate::proxy::Proxy;
 use crate::reader::Reader;
-use crate::requests::RequestHandler;
+use crate::requests::{PoolConfig, RequestHandler};
+use crate::scrub::{run_scrub_worker, ScrubTranquility};
 use crate::utils::{get_bus_type, get_device_id, new_thread_pool};
+use crate::worker::{WorkerCommand, WorkerRegistry, WorkerState};
 use crossbeam_channel;
 use filetime::FileTime;
 use futures_util::{stream::StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 #[cfg(feature = "opencl")]
@@ -25,8 +34,11 @@ use ocl_core::Mem;
 use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::fs::read_dir;
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::net::SocketAddr;
 use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 #[cfg(feature = "async_io")]
 use tokio::sync::Mutex;
@@ -38,6 +50,7 @@ use std::thread;
 use std::u64;
 use stopwatch::Stopwatch;
 use tokio::runtime::Handle;
+use url::Url;
 
 
 
@@ -57,6 +70,20 @@ pub struct Miner {
     executor: Handle,
     wakeup_after: i64,
     submit_only_best: bool,
+    long_poll_enabled: bool,
+    long_poll_timeout_ms: u64,
+    pubsub_url: Option<Url>,
+    /// Address to serve Prometheus-style text metrics from, if configured.
+    metrics_listen_addr: Option<SocketAddr>,
+    /// Address to run the multi-miner aggregation proxy on, if configured.
+    proxy_listen_addr: Option<SocketAddr>,
+    /// Address to accept worker-control commands on (`status`/`pause
+    /// <name>`/`start <name>`/`cancel <name>`), if configured.
+    control_listen_addr: Option<SocketAddr>,
+    worker_registry: WorkerRegistry,
+    scrub_enabled: bool,
+    scrub_tranquility_ms: u64,
+    block_generation: Arc<AtomicU64>,
 }
 
 pub struct State {
@@ -68,11 +95,13 @@ pub struct State {
     server_target_deadline: u64,
     base_target: u64,
     sw: Stopwatch,
-    scanning: bool,
+    pub(crate) scanning: bool,
     processed_reader_tasks: usize,
     scoop: u32,
     first: bool,
     outage: bool,
+    deadline_histogram: Histogram,
+    round_time_histogram: Histogram,
 }
 
 impl State {
@@ -91,15 +120,32 @@ impl State {
             scanning: false,
             first: true,
             outage: false,
+            deadline_histogram: Histogram::new(),
+            round_time_histogram: Histogram::new(),
         }
     }
 
-    fn update_mining_info(&mut self, mining_info: &MiningInfo) {
+    /// Snapshot of this block's deadline distribution so far.
+    pub(crate) fn deadline_snapshot(&self) -> HistogramSnapshot {
+        self.deadline_histogram.snapshot()
+    }
+
+    /// Snapshot of the rolling round-time distribution across blocks.
+    pub(crate) fn round_time_snapshot(&self) -> HistogramSnapshot {
+        self.round_time_histogram.snapshot()
+    }
+
+    fn update_mining_info(&mut self, mining_info: &MiningInfo, block_generation: &AtomicU64) {
         for best_deadlines in self.account_id_to_best_deadline.values_mut() {
             *best_deadlines = u64::MAX;
         }
         self.height = mining_info.height;
         self.block += 1;
+        // Readable by the CPU hasher without taking the `State` lock, so it
+        // can cheaply skip a task for a block that's already gone instead of
+        // hashing a scoop nobody wants the result of anymore (see
+        // `cpu_worker::hash`). Reader threads are not handed this counter.
+        block_generation.store(self.block, Ordering::SeqCst);
         self.base_target = mining_info.base_target;
         self.server_target_deadline = mining_info.target_deadline;
 
@@ -118,6 +164,10 @@ impl State {
         self.sw.restart();
         self.processed_reader_tasks = 0;
         self.scanning = true;
+        // Deadlines only make sense relative to the current block's scoop;
+        // the round-time/submit-latency histograms stay rolling across
+        // blocks since those distributions are meaningful over time.
+        self.deadline_histogram.reset();
     }
 }
 
@@ -243,6 +293,125 @@ fn scan_plots(
     (drive_id_to_plots, global_capacity * 64)
 }
 
+/// Builds the live per-round progress bar, or a hidden no-op bar when stdout
+/// isn't a TTY (e.g. running under a service manager or piped to a log
+/// file), so callers don't need to branch on terminal-ness themselves.
+fn make_progress_bar(reader_task_count: u64) -> ProgressBar {
+    if !std::io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new(reader_task_count);
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} round [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} drives, {msg} (eta {eta})",
+        )
+        .unwrap()
+        .progress_chars("#>-"),
+    );
+    pb
+}
+
+/// Sends `cmd` to the named worker via the control socket, turning
+/// `WorkerRegistry::send_command`'s bool result into the human-readable
+/// reply the socket writes back.
+async fn dispatch_control_command(
+    registry: &WorkerRegistry,
+    name: &str,
+    cmd: WorkerCommand,
+) -> String {
+    if registry.send_command(name, cmd).await {
+        format!("ok: {:?} sent to '{}'", cmd, name)
+    } else {
+        format!("error: no worker registered as '{}'", name)
+    }
+}
+
+/// Applies a freshly fetched `MiningInfoResponse` (or error) to shared miner
+/// state. Shared between the fixed-interval poll and the long-poll task so
+/// both funnel through the same gensig-change guard and outage bookkeeping,
+/// instead of duplicating it for each source of mining info.
+async fn apply_mining_info_result(
+    result: Result<MiningInfo, FetchError>,
+    state: &Arc<Mutex<State>>,
+    reader: &Arc<Mutex<Reader>>,
+    wakeup_after: i64,
+    block_generation: &Arc<AtomicU64>,
+    progress_bar: &Arc<Mutex<Option<ProgressBar>>>,
+    reader_task_count: u64,
+    proxy: Option<&Arc<Proxy>>,
+) {
+    match result {
+        Ok(mining_info) => {
+            #[cfg(feature = "async_io")]
+            let mut state = state.lock().await;
+            #[cfg(not(feature = "async_io"))]
+            let mut state = state.lock().unwrap();
+            state.first = false;
+            if state.outage {
+                error!("{: <80}", "outage resolved.");
+                state.outage = false;
+            }
+            if mining_info.generation_signature != state.generation_signature {
+                state.update_mining_info(&mining_info, block_generation);
+                #[cfg(feature = "async_io")]
+                reader.lock().await.start_reading(
+                    mining_info.height,
+                    state.block,
+                    mining_info.base_target,
+                    state.scoop,
+                    &Arc::new(state.generation_signature_bytes),
+                );
+                #[cfg(not(feature = "async_io"))]
+                reader.lock().unwrap().start_reading(
+                    mining_info.height,
+                    state.block,
+                    mining_info.base_target,
+                    state.scoop,
+                    &Arc::new(state.generation_signature_bytes),
+                );
+                drop(state);
+
+                if let Some(proxy) = proxy {
+                    proxy
+                        .update_mining_info(mining_info.clone(), block_generation.load(Ordering::SeqCst))
+                        .await;
+                }
+
+                #[cfg(feature = "async_io")]
+                {
+                    *progress_bar.lock().await = Some(make_progress_bar(reader_task_count));
+                }
+                #[cfg(not(feature = "async_io"))]
+                {
+                    *progress_bar.lock().unwrap() = Some(make_progress_bar(reader_task_count));
+                }
+            } else if !state.scanning && wakeup_after != 0 && state.sw.elapsed_ms() > wakeup_after {
+                info!("HDD, wakeup!");
+                #[cfg(feature = "async_io")]
+                reader.lock().await.wakeup();
+                #[cfg(not(feature = "async_io"))]
+                reader.lock().unwrap().wakeup();
+                state.sw.restart();
+            }
+        }
+        _ => {
+            #[cfg(feature = "async_io")]
+            let mut state = state.lock().await;
+            #[cfg(not(feature = "async_io"))]
+            let mut state = state.lock().unwrap();
+            if state.first {
+                error!("{: <80}", "error getting mining info, please check server config");
+                state.first = false;
+                state.outage = true;
+            } else if !state.outage {
+                error!("{: <80}", "error getting mining info => connection outage...");
+                state.outage = true;
+            }
+        }
+    }
+}
+
 impl Miner {
     pub fn new(cfg: Cfg, executor: Handle) -> Miner {
         let (drive_id_to_plots, total_size) =
@@ -252,6 +421,11 @@ impl Miner {
         info!("🖥️  Using {} CPU thread(s)", cpu_threads);
         let cpu_worker_task_count = cfg.cpu_worker_task_count;
 
+        // Shared by every reader/hasher worker thread below so pinning stays
+        // a single round-robin rotation across the whole miner rather than
+        // each subsystem picking cores independently.
+        let core_assigner = CoreAssigner::new(&cfg.cpu_core_pinning);
+
         let cpu_buffer_count = cpu_worker_task_count
             + if cpu_worker_task_count > 0 {
                 cpu_threads
@@ -396,15 +570,43 @@ impl Miner {
 
         let (tx_nonce_data, rx_nonce_data) = mpsc::channel(buffer_count);
 
-        thread::spawn({
-            create_cpu_worker_task(
-                cfg.benchmark_io(),
-                new_thread_pool(cpu_threads, cfg.cpu_thread_pinning),
-                rx_read_replies_cpu.clone(),
-                tx_empty_buffers.clone(),
-                tx_nonce_data.clone(),
-            )
-        });
+        // Bumped in `State::update_mining_info` whenever a new block starts;
+        // the CPU worker compares each in-flight task's block against this
+        // to skip stale work early instead of hashing a scoop for a block
+        // that's already gone, see `cpu_worker::hash`. The reader and the
+        // GPU workers are not handed this counter.
+        let block_generation = Arc::new(AtomicU64::new(0));
+
+        // Constructed here (rather than in the `Miner { .. }` literal below)
+        // so the same registry instance can be handed to the CPU-worker
+        // thread spawned next; it's cloned into the struct field afterwards.
+        let worker_registry = WorkerRegistry::new();
+
+        {
+            // `new_thread_pool` pins each of its own rayon threads via the
+            // same assigner, so the dedicated thread below (which just feeds
+            // reads into that pool) and the pool's workers never fight over
+            // the same core.
+            let cpu_core_assigner = core_assigner.clone();
+            let benchmark_io = cfg.benchmark_io();
+            let cpu_worker_registry = worker_registry.clone();
+            let cpu_worker_executor = executor.clone();
+            thread::spawn(move || {
+                if let Some(assigner) = &cpu_core_assigner {
+                    assigner.pin_current_thread("cpu-worker");
+                }
+                create_cpu_worker_task(
+                    benchmark_io,
+                    new_thread_pool(cpu_threads, cpu_core_assigner.clone()),
+                    rx_read_replies_cpu.clone(),
+                    tx_empty_buffers.clone(),
+                    tx_nonce_data.clone(),
+                    block_generation.clone(),
+                    cpu_worker_registry,
+                    cpu_worker_executor,
+                )()
+            });
+        }
 
         #[cfg(feature = "opencl")]
         for i in 0..gpu_threads {
@@ -454,27 +656,42 @@ impl Miner {
                 tx_read_replies_gpu,
                 cfg.show_progress,
                 cfg.show_drive_stats,
-                cfg.cpu_thread_pinning,
+                core_assigner.clone(),
                 cfg.benchmark_cpu(),
             ))), // three closing parens
             rx_nonce_data,
             target_deadline: cfg.target_deadline,
             account_id_to_target_deadline: cfg.account_id_to_target_deadline,
-            request_handler: Arc::new(Mutex::new(RequestHandler::new(
-                cfg.url,
-                cfg.account_id_to_secret_phrase,
-                cfg.timeout,
-                (total_size * 4 / 1024 / 1024) as usize,
-                cfg.send_proxy_details,
-                cfg.additional_headers,
-                executor.clone(),
-            ))), // three closing parens
+            request_handler: Arc::new(Mutex::new({
+                let pools = RequestHandler::resolve_pools(cfg.pools, cfg.url, cfg.account_id_to_secret_phrase);
+
+                RequestHandler::with_pools(
+                    pools,
+                    cfg.pool_strategy,
+                    cfg.timeout,
+                    (total_size * 4 / 1024 / 1024) as usize,
+                    cfg.send_proxy_details,
+                    cfg.additional_headers,
+                    executor.clone(),
+                    cfg.max_submit_retries,
+                )
+            })), // three closing parens
             state: Arc::new(Mutex::new(State::new())),
             // floor at 1s to protect servers
             get_mining_info_interval: max(1000, cfg.get_mining_info_interval),
             executor,
             wakeup_after: cfg.hdd_wakeup_after * 1000, // ms -> s
             submit_only_best : cfg.submit_only_best,
+            long_poll_enabled: cfg.long_poll_enabled,
+            long_poll_timeout_ms: cfg.long_poll_timeout_ms,
+            pubsub_url: cfg.pubsub_url,
+            metrics_listen_addr: cfg.metrics_listen_addr,
+            proxy_listen_addr: cfg.proxy_listen_addr,
+            control_listen_addr: cfg.control_listen_addr,
+            worker_registry,
+            scrub_enabled: cfg.scrub_enabled,
+            scrub_tranquility_ms: cfg.scrub_tranquility_ms,
+            block_generation,
         }
     }
 
@@ -510,6 +727,28 @@ impl Miner {
         }
     }
 
+    /// Shared handle to the pool list, for `main`'s SIGHUP handler to pass to
+    /// `RequestHandler::reload` without needing a reference into `Miner`
+    /// itself (which `run` below consumes by value).
+    pub fn request_handler(&self) -> Arc<Mutex<RequestHandler>> {
+        self.request_handler.clone()
+    }
+
+    /// Current total plot capacity in GB, for `main`'s SIGHUP handler to seed
+    /// a reloaded pool list with instead of starting it back at zero; the
+    /// next periodic `refresh_capacity` corrects it either way.
+    #[cfg(feature = "async_io")]
+    pub async fn total_size_gb(&self) -> usize {
+        let total_size = self.reader.lock().await.total_size;
+        (total_size * 4 / 1024 / 1024) as usize
+    }
+
+    #[cfg(not(feature = "async_io"))]
+    pub fn total_size_gb(&self) -> usize {
+        let total_size = self.reader.lock().unwrap().total_size;
+        (total_size * 4 / 1024 / 1024) as usize
+    }
+
     pub async fn run(self) {
         use tokio::time::{sleep, Duration};
         let mut miner = Arc::new(self);
@@ -530,19 +769,79 @@ impl Miner {
 
         let reader = miner.reader.clone();
 
+        let block_generation = miner.block_generation.clone();
 
         let state = miner.state.clone();
         // there might be a way to solve this without two nested moves
         let get_mining_info_interval = miner.get_mining_info_interval;
         let wakeup_after = miner.wakeup_after;
+        let long_poll_enabled = miner.long_poll_enabled;
+        let long_poll_timeout_ms = miner.long_poll_timeout_ms;
+        let pubsub_url = miner.pubsub_url.clone();
+
+        // Multi-miner aggregation proxy: downstream miners hit this instead
+        // of the upstream pool directly, so the whole farm shares one
+        // upstream connection and one retry/backoff pipeline. Disabled by
+        // default (`proxy_listen_addr` is `None`).
+        let proxy = miner
+            .proxy_listen_addr
+            .map(|addr| (Arc::new(Proxy::new(request_handler.clone())), addr));
+        if let Some((proxy, addr)) = proxy.clone() {
+            let proxy_for_server = proxy.clone();
+            tokio::spawn(async move { proxy_for_server.run(addr).await });
+            tokio::spawn(async move {
+                loop {
+                    sleep(Duration::from_secs(60)).await;
+                    proxy.refresh_upstream_capacity().await;
+                }
+            });
+        }
+        let proxy = proxy.map(|(proxy, _)| proxy);
+
+        let reader_task_count = miner.reader_task_count as u64;
+        // Shared with the nonce-consumer loop below: filled in by
+        // `apply_mining_info_result` (from the poll, long-poll or pubsub
+        // task) the moment a new round starts, updated there as reader tasks
+        // complete, and finalized when the round ends.
+        let progress_bar: Arc<Mutex<Option<ProgressBar>>> = Arc::new(Mutex::new(None));
+
+        let (poll_worker, mut poll_cmd_rx) = miner.worker_registry.register("mining-info-poll").await;
+        let poll_paused = Arc::new(AtomicBool::new(false));
+        let poll_cancelled = Arc::new(AtomicBool::new(false));
+
+        let poll_progress_bar = progress_bar.clone();
+        let poll_proxy = proxy.clone();
         tokio::spawn(async move {
             info!("→ Interval task started");
+            let take_while_cancelled = poll_cancelled.clone();
             Interval::new_interval(Duration::from_millis(get_mining_info_interval))
+                .take_while(move |_| {
+                    let cancelled = take_while_cancelled.clone();
+                    async move { !cancelled.load(Ordering::SeqCst) }
+                })
                 .for_each(move |_| {
+                    while let Ok(cmd) = poll_cmd_rx.try_recv() {
+                        match cmd {
+                            WorkerCommand::Start => poll_paused.store(false, Ordering::SeqCst),
+                            WorkerCommand::Pause => poll_paused.store(true, Ordering::SeqCst),
+                            WorkerCommand::Cancel => poll_cancelled.store(true, Ordering::SeqCst),
+                        }
+                    }
+
+                    let paused = poll_paused.load(Ordering::SeqCst);
                     let state = state.clone();
                     let reader = reader.clone();
                     let request_handler = request_handler.clone();
+                    let poll_worker = poll_worker.clone();
+                    let block_generation = block_generation.clone();
+                    let progress_bar = poll_progress_bar.clone();
+                    let poll_proxy = poll_proxy.clone();
                     async move {
+                        if paused {
+                            poll_worker.report(WorkerState::Idle).await;
+                            return;
+                        }
+
                         #[cfg(feature = "async_io")]
                         let mining_info_fut = {
                             let rh = request_handler.lock().await.clone();
@@ -553,86 +852,406 @@ impl Miner {
                             let rh = request_handler.lock().unwrap().clone();
                             async move { rh.get_mining_info().await }
                         };
-                        match mining_info_fut.await {
-                            Ok(mining_info) => {
-                                #[cfg(feature = "async_io")]
-                                let mut state = state.lock().await;
-                                #[cfg(not(feature = "async_io"))]
-                                let mut state = state.lock().unwrap();
-                                state.first = false;
-                                if state.outage {
-                                    error!("{: <80}", "outage resolved.");
-                                    state.outage = false;
+                        let result = mining_info_fut.await;
+                        if let Err(e) = &result {
+                            poll_worker.report_error(e).await;
+                        }
+                        apply_mining_info_result(
+                            result,
+                            &state,
+                            &reader,
+                            wakeup_after,
+                            &block_generation,
+                            &progress_bar,
+                            reader_task_count,
+                            poll_proxy.as_ref(),
+                        )
+                        .await;
+                        poll_worker.report(WorkerState::Active).await;
+                    }
+                })
+                .await;
+        });
+
+        // Long-poll mode: ask the active pool to hold the request open and push
+        // back the next `MiningInfoResponse` as soon as the generation signature
+        // changes, instead of waiting for the next fixed-interval tick above. Both
+        // tasks funnel through `apply_mining_info_result`, which is guarded by the
+        // same `generation_signature != state.generation_signature` check, so a
+        // block is never processed twice regardless of which task noticed it
+        // first. Driven by `RequestHandler::subscribe_mining_info`, which ends the
+        // stream on its own once it decides the active pool doesn't really support
+        // long-polling, so this task just runs its course and the `Interval` task
+        // above keeps running unconditionally as the permanent heartbeat/fallback.
+        if long_poll_enabled {
+            let state = miner.state.clone();
+            let reader = miner.reader.clone();
+            let request_handler = miner.request_handler.clone();
+            let block_generation = miner.block_generation.clone();
+            let progress_bar = progress_bar.clone();
+            let longpoll_proxy = proxy.clone();
+            let wait_timeout = Duration::from_millis(long_poll_timeout_ms);
+            let (longpoll_worker, mut longpoll_cmd_rx) =
+                miner.worker_registry.register("mining-info-longpoll").await;
+            tokio::spawn(async move {
+                info!("→ Long-poll task started");
+                let mut paused = false;
+
+                // Re-cloned every time this outer loop (re)starts: a stream-ended
+                // break (pool doesn't support long-polling) and the periodic
+                // `refresh_interval` tick below both loop back here, so a
+                // `RequestHandler` swapped in by the SIGHUP pool reload (see
+                // `main.rs`) gets picked up instead of this task holding on to
+                // the pre-reload pool list and `Client`s forever.
+                'reconnect: loop {
+                    #[cfg(feature = "async_io")]
+                    let rh = request_handler.lock().await.clone();
+                    #[cfg(not(feature = "async_io"))]
+                    let rh = request_handler.lock().unwrap().clone();
+
+                    let mut subscription = Box::pin(rh.subscribe_mining_info(wait_timeout));
+                    // Forces a fresh `rh` clone periodically even while
+                    // long-polling is working fine, since a healthy subscription
+                    // never ends on its own and would otherwise never notice a
+                    // reload.
+                    let mut refresh_interval = tokio::time::interval(Duration::from_secs(300));
+                    refresh_interval.tick().await; // first tick fires immediately
+
+                    loop {
+                        if paused {
+                            longpoll_worker.report(WorkerState::Idle).await;
+                            match longpoll_cmd_rx.recv().await {
+                                Some(WorkerCommand::Start) => paused = false,
+                                Some(WorkerCommand::Pause) => {}
+                                Some(WorkerCommand::Cancel) | None => break 'reconnect,
+                            }
+                            continue;
+                        }
+
+                        tokio::select! {
+                            _ = refresh_interval.tick() => {
+                                continue 'reconnect;
+                            }
+                            next = subscription.next() => {
+                                let Some(result) = next else {
+                                    warn!("long-poll: active pool doesn't appear to support it, re-deriving from the current pool list and relying on fixed-interval polling meanwhile");
+                                    // Avoid hammering a pool that never supports
+                                    // long-polling with back-to-back resubscribes.
+                                    sleep(Duration::from_secs(30)).await;
+                                    continue 'reconnect;
+                                };
+                                if let Err(e) = &result {
+                                    longpoll_worker.report_error(e).await;
                                 }
-                                if mining_info.generation_signature != state.generation_signature {
-                                    state.update_mining_info(&mining_info);
-                                    #[cfg(feature = "async_io")]
-                                    reader.lock().await.start_reading(
-                                        mining_info.height,
-                                        state.block,
-                                        mining_info.base_target,
-                                        state.scoop,
-                                        &Arc::new(state.generation_signature_bytes),
-                                    );
-                                    #[cfg(not(feature = "async_io"))]
-                                    reader.lock().unwrap().start_reading(
-                                        mining_info.height,
-                                        state.block,
-                                        mining_info.base_target,
-                                        state.scoop,
-                                        &Arc::new(state.generation_signature_bytes),
-                                    );
-                                    drop(state);
-                                } else if !state.scanning
-                                    && wakeup_after != 0
-                                    && state.sw.elapsed_ms() > wakeup_after
-                                {
-                                    info!("HDD, wakeup!");
-                                    #[cfg(feature = "async_io")]
-                                    reader.lock().await.wakeup();
-                                    #[cfg(not(feature = "async_io"))]
-                                    reader.lock().unwrap().wakeup();
-                                    state.sw.restart();
+                                apply_mining_info_result(
+                                    result,
+                                    &state,
+                                    &reader,
+                                    wakeup_after,
+                                    &block_generation,
+                                    &progress_bar,
+                                    reader_task_count,
+                                    longpoll_proxy.as_ref(),
+                                )
+                                .await;
+                                longpoll_worker.report(WorkerState::Active).await;
+                            }
+                            cmd = longpoll_cmd_rx.recv() => {
+                                match cmd {
+                                    Some(WorkerCommand::Start) => {}
+                                    Some(WorkerCommand::Pause) => paused = true,
+                                    Some(WorkerCommand::Cancel) | None => break 'reconnect,
                                 }
                             }
-                            _ => {
-                                #[cfg(feature = "async_io")]
-                                let mut state = state.lock().await;
-                                #[cfg(not(feature = "async_io"))]
-                                let mut state = state.lock().unwrap();
-                                if state.first {
-                                    error!(
-                                        "{: <80}",
-                                        "error getting mining info, please check server config"
-                                    );
-                                    state.first = false;
-                                    state.outage = true;
-                                } else if !state.outage {
-                                    error!(
-                                        "{: <80}",
-                                        "error getting mining info => connection outage..."
-                                    );
-                                    state.outage = true;
+                        }
+                    }
+                }
+                longpoll_worker.report(WorkerState::Dead).await;
+            });
+        }
+
+        // Pubsub mode: subscribe to the pool's WebSocket push endpoint so a
+        // new block is applied the instant it's announced, rather than
+        // waiting for the next poll/long-poll round-trip. Reconnects with a
+        // capped backoff on any drop; the `Interval` task above keeps
+        // running unconditionally as the fallback for the (common) case
+        // where the pool doesn't expose a subscription endpoint at all.
+        if let Some(pubsub_url) = pubsub_url {
+            let state = miner.state.clone();
+            let reader = miner.reader.clone();
+            let block_generation = miner.block_generation.clone();
+            let progress_bar = progress_bar.clone();
+            let pubsub_proxy = proxy.clone();
+            let (pubsub_worker, mut pubsub_cmd_rx) =
+                miner.worker_registry.register("mining-info-pubsub").await;
+            tokio::spawn(async move {
+                info!("→ Pubsub task started ({})", pubsub_url);
+                let mut paused = false;
+                let mut reconnect_delay = Duration::from_secs(1);
+                let max_reconnect_delay = Duration::from_secs(30);
+
+                loop {
+                    if paused {
+                        pubsub_worker.report(WorkerState::Idle).await;
+                        match pubsub_cmd_rx.recv().await {
+                            Some(WorkerCommand::Start) => paused = false,
+                            Some(WorkerCommand::Pause) => {}
+                            Some(WorkerCommand::Cancel) | None => break,
+                        }
+                        continue;
+                    }
+
+                    let mut client = match PubsubClient::connect(&pubsub_url).await {
+                        Ok(client) => {
+                            reconnect_delay = Duration::from_secs(1);
+                            client
+                        }
+                        Err(e) => {
+                            pubsub_worker.report_error(&e).await;
+                            warn!(
+                                "pubsub: could not subscribe to {}, falling back to polling for now (retrying in {}s): {}",
+                                pubsub_url, reconnect_delay.as_secs(), e
+                            );
+                            sleep(reconnect_delay).await;
+                            reconnect_delay = (reconnect_delay * 2).min(max_reconnect_delay);
+                            continue;
+                        }
+                    };
+
+                    loop {
+                        tokio::select! {
+                            result = client.next_mining_info() => {
+                                match result {
+                                    Ok(mining_info) => {
+                                        apply_mining_info_result(
+                                            Ok(mining_info),
+                                            &state,
+                                            &reader,
+                                            wakeup_after,
+                                            &block_generation,
+                                            &progress_bar,
+                                            reader_task_count,
+                                            pubsub_proxy.as_ref(),
+                                        )
+                                        .await;
+                                        pubsub_worker.report(WorkerState::Active).await;
+                                    }
+                                    Err(e) => {
+                                        pubsub_worker.report_error(&e).await;
+                                        warn!("pubsub: subscription dropped, falling back to polling until reconnect: {}", e);
+                                        break;
+                                    }
+                                }
+                            }
+                            cmd = pubsub_cmd_rx.recv() => {
+                                match cmd {
+                                    Some(WorkerCommand::Start) => {}
+                                    Some(WorkerCommand::Pause) => { paused = true; break; }
+                                    Some(WorkerCommand::Cancel) | None => {
+                                        pubsub_worker.report(WorkerState::Dead).await;
+                                        return;
+                                    }
                                 }
                             }
                         }
                     }
-                })
-                .await;
-        });
+
+                    if !paused {
+                        sleep(reconnect_delay).await;
+                        reconnect_delay = (reconnect_delay * 2).min(max_reconnect_delay);
+                    }
+                }
+                pubsub_worker.report(WorkerState::Dead).await;
+            });
+        }
+
+        // Minimal hand-rolled HTTP server for the Prometheus text endpoint:
+        // there's no web framework in this binary's dependency tree, and a
+        // metrics scrape doesn't need more than "read the request, ignore
+        // it, write the response".
+        if let Some(addr) = miner.metrics_listen_addr {
+            let request_handler = request_handler.clone();
+            tokio::spawn(async move {
+                let listener = match TcpListener::bind(addr).await {
+                    Ok(listener) => listener,
+                    Err(e) => {
+                        error!("metrics: could not bind to {}: {}", addr, e);
+                        return;
+                    }
+                };
+                info!("→ Metrics endpoint listening on http://{}/metrics", addr);
+
+                loop {
+                    let (mut socket, _) = match listener.accept().await {
+                        Ok(conn) => conn,
+                        Err(e) => {
+                            warn!("metrics: accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let request_handler = request_handler.clone();
+                    tokio::spawn(async move {
+                        // Discard the request line/headers; the same body is
+                        // served regardless of path or method.
+                        let mut buf = [0u8; 1024];
+                        let _ = socket.read(&mut buf).await;
+
+                        #[cfg(feature = "async_io")]
+                        let body = { request_handler.lock().await.render_metrics() };
+                        #[cfg(not(feature = "async_io"))]
+                        let body = { request_handler.lock().unwrap().render_metrics() };
+
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        let _ = socket.write_all(response.as_bytes()).await;
+                    });
+                }
+            });
+        }
 
         let miner_refresh = miner.clone();
+        let (capacity_worker, mut capacity_cmd_rx) =
+            miner.worker_registry.register("capacity-refresh").await;
+        let capacity_paused = Arc::new(AtomicBool::new(false));
+        let capacity_cancelled = Arc::new(AtomicBool::new(false));
         tokio::spawn(async move {
+            let take_while_cancelled = capacity_cancelled.clone();
             Interval::new_interval(Duration::from_secs(miner_refresh.capacity_check_interval))
+                .take_while(move |_| {
+                    let cancelled = take_while_cancelled.clone();
+                    async move { !cancelled.load(Ordering::SeqCst) }
+                })
                 .for_each(move |_| {
+                    while let Ok(cmd) = capacity_cmd_rx.try_recv() {
+                        match cmd {
+                            WorkerCommand::Start => capacity_paused.store(false, Ordering::SeqCst),
+                            WorkerCommand::Pause => capacity_paused.store(true, Ordering::SeqCst),
+                            WorkerCommand::Cancel => capacity_cancelled.store(true, Ordering::SeqCst),
+                        }
+                    }
+
+                    let paused = capacity_paused.load(Ordering::SeqCst);
                     let miner_refresh = miner_refresh.clone();
+                    let capacity_worker = capacity_worker.clone();
                     async move {
+                        if paused {
+                            capacity_worker.report(WorkerState::Idle).await;
+                            return;
+                        }
                         miner_refresh.refresh_capacity().await;
+                        capacity_worker.report(WorkerState::Active).await;
                     }
                 })
                 .await;
         });
 
+        // Status query surface for the worker registry: send the running
+        // process `SIGUSR1` (e.g. `kill -USR1 $(pidof signum-miner)`) to have
+        // it log every registered worker's name, state, iteration count and
+        // last error, without pausing or killing anything. Unix-only since
+        // there is no equivalent signal on Windows.
+        #[cfg(unix)]
+        {
+            let worker_registry = miner.worker_registry.clone();
+            tokio::spawn(async move {
+                let mut sigusr1 = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined1()) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        warn!("could not install SIGUSR1 handler for worker status queries: {}", e);
+                        return;
+                    }
+                };
+                loop {
+                    sigusr1.recv().await;
+                    info!("{}", worker_registry.format_status_report().await);
+                }
+            });
+        }
+
+        // Control surface for the worker registry: accepts line-based
+        // commands over plain TCP (`status`, `pause <name>`, `start <name>`,
+        // `cancel <name>`) and dispatches to `WorkerRegistry::send_command`.
+        // Kept as a separate opt-in listener from the metrics endpoint since
+        // one is read-only scraping and the other can change what's running.
+        if let Some(addr) = miner.control_listen_addr {
+            let worker_registry = miner.worker_registry.clone();
+            tokio::spawn(async move {
+                let listener = match TcpListener::bind(addr).await {
+                    Ok(listener) => listener,
+                    Err(e) => {
+                        error!("control: could not bind to {}: {}", addr, e);
+                        return;
+                    }
+                };
+                info!("→ Worker control endpoint listening on {}", addr);
+
+                loop {
+                    let (mut socket, _) = match listener.accept().await {
+                        Ok(conn) => conn,
+                        Err(e) => {
+                            warn!("control: accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let worker_registry = worker_registry.clone();
+                    tokio::spawn(async move {
+                        let mut buf = [0u8; 256];
+                        let n = match socket.read(&mut buf).await {
+                            Ok(n) => n,
+                            Err(_) => return,
+                        };
+                        let line = String::from_utf8_lossy(&buf[..n]);
+                        let mut parts = line.trim().splitn(2, ' ');
+                        let response = match (parts.next(), parts.next()) {
+                            (Some("status"), _) => worker_registry.format_status_report().await,
+                            (Some("pause"), Some(name)) => {
+                                dispatch_control_command(&worker_registry, name, WorkerCommand::Pause).await
+                            }
+                            (Some("start"), Some(name)) => {
+                                dispatch_control_command(&worker_registry, name, WorkerCommand::Start).await
+                            }
+                            (Some("cancel"), Some(name)) => {
+                                dispatch_control_command(&worker_registry, name, WorkerCommand::Cancel).await
+                            }
+                            _ => "error: expected 'status', 'pause <name>', 'start <name>' or 'cancel <name>'".to_owned(),
+                        };
+                        let _ = socket.write_all(format!("{}\n", response).as_bytes()).await;
+                    });
+                }
+            });
+        }
+
+        // Background plot-integrity scrubber: rescans the same plot
+        // directories `refresh_capacity` already walks periodically, so it
+        // opens its own file handles rather than sharing the `Reader`'s.
+        if miner.scrub_enabled {
+            let plot_dirs = miner.plot_dirs.clone();
+            let hdd_use_direct_io = miner.hdd_use_direct_io;
+            let benchmark_cpu = miner.benchmark_cpu;
+            let state = miner.state.clone();
+            let tranquility = ScrubTranquility(miner.scrub_tranquility_ms);
+            let progress_dir = plot_dirs
+                .first()
+                .cloned()
+                .unwrap_or_else(|| PathBuf::from("."));
+            let worker_registry = miner.worker_registry.clone();
+            tokio::spawn(async move {
+                let (drive_id_to_plots, _total_size) =
+                    scan_plots(&plot_dirs, hdd_use_direct_io, benchmark_cpu);
+                run_scrub_worker(
+                    drive_id_to_plots,
+                    state,
+                    tranquility,
+                    progress_dir,
+                    worker_registry,
+                )
+                .await;
+            });
+        }
+
         // only start submitting nonces after a while
         let mut best_nonce_data = NonceData {
             height: 0,
@@ -656,6 +1275,7 @@ impl Miner {
                     let state = state.clone();
                     let request_handler = request_handler.clone();
                     let account_id_to_target_deadline = account_id_to_target_deadline.clone();
+                    let progress_bar = progress_bar.clone();
                     async move {
                         #[cfg(feature = "async_io")]
                         let mut state = state.lock().await;
@@ -663,6 +1283,14 @@ impl Miner {
                         let mut state = state.lock().unwrap();
 
                         let deadline = nonce_data.deadline / nonce_data.base_target;
+                        // Work restart: a reader/hasher task dispatched for a block that has
+                        // since been superseded can still land here after the new block's
+                        // tasks are already running; `nonce_data.height` alone doesn't catch
+                        // this reliably, so compare against the block counter directly.
+                        if nonce_data.block < state.block {
+                            return;
+                        }
+                        state.deadline_histogram.record(deadline);
                         if state.height == nonce_data.height {
                             let best_deadline = *state
                                 .account_id_to_best_deadline
@@ -709,19 +1337,81 @@ impl Miner {
 
                             if nonce_data.reader_task_processed {
                                 state.processed_reader_tasks += 1;
+
+                                let best_deadline = state
+                                    .account_id_to_best_deadline
+                                    .values()
+                                    .copied()
+                                    .min()
+                                    .unwrap_or(u64::MAX);
+                                let speed = total_size as f64 * 1000.0
+                                    / 1024.0
+                                    / 1024.0
+                                    / state.sw.elapsed_ms().max(1) as f64;
+                                #[cfg(feature = "async_io")]
+                                let pb_guard = progress_bar.lock().await;
+                                #[cfg(not(feature = "async_io"))]
+                                let pb_guard = progress_bar.lock().unwrap();
+                                if let Some(pb) = pb_guard.as_ref() {
+                                    pb.set_position(state.processed_reader_tasks as u64);
+                                    pb.set_message(format!(
+                                        "{:.2}MiB/s, best deadline={}",
+                                        speed,
+                                        if best_deadline == u64::MAX {
+                                            "-".to_owned()
+                                        } else {
+                                            best_deadline.to_string()
+                                        }
+                                    ));
+                                }
+                                drop(pb_guard);
+
                                 if state.processed_reader_tasks == reader_task_count {
+                                    state.round_time_histogram.record(state.sw.elapsed_ms());
+                                    let deadline_snapshot = state.deadline_snapshot();
+                                    let round_time_snapshot = state.round_time_snapshot();
+                                    #[cfg(feature = "async_io")]
+                                    let submit_latency_snapshot =
+                                        request_handler.lock().await.submit_latency_snapshot().await;
+                                    #[cfg(not(feature = "async_io"))]
+                                    let submit_latency_snapshot =
+                                        request_handler.lock().unwrap().submit_latency_snapshot();
+                                    let summary = format!(
+                                        "round finished: roundtime={}ms, speed={:.2}MiB/s",
+                                        state.sw.elapsed_ms(),
+                                        speed
+                                    );
+                                    info!("{: <80}", summary);
                                     info!(
                                         "{: <80}",
                                         format!(
-                                            "round finished: roundtime={}ms, speed={:.2}MiB/s",
-                                            state.sw.elapsed_ms(),
-                                            total_size as f64 * 1000.0
-                                                / 1024.0
-                                                / 1024.0
-                                                / state.sw.elapsed_ms() as f64
+                                            "deadlines: p50={} p90={} p99={} max={} (n={}); roundtime: p50={}ms p90={}ms p99={}ms max={}ms (n={}); submit latency: p50={}ms p90={}ms p99={}ms max={}ms (n={})",
+                                            deadline_snapshot.p50,
+                                            deadline_snapshot.p90,
+                                            deadline_snapshot.p99,
+                                            deadline_snapshot.max,
+                                            deadline_snapshot.count,
+                                            round_time_snapshot.p50,
+                                            round_time_snapshot.p90,
+                                            round_time_snapshot.p99,
+                                            round_time_snapshot.max,
+                                            round_time_snapshot.count,
+                                            submit_latency_snapshot.p50,
+                                            submit_latency_snapshot.p90,
+                                            submit_latency_snapshot.p99,
+                                            submit_latency_snapshot.max,
+                                            submit_latency_snapshot.count,
                                         )
                                     );
 
+                                    #[cfg(feature = "async_io")]
+                                    let finished_bar = progress_bar.lock().await.take();
+                                    #[cfg(not(feature = "async_io"))]
+                                    let finished_bar = progress_bar.lock().unwrap().take();
+                                    if let Some(pb) = finished_bar {
+                                        pb.finish_with_message(summary);
+                                    }
+
                                     // Submit now our best one, if configured that way
                                     if best_nonce_data.height == state.height {
                                         let deadline =
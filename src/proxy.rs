@@ -0,0 +1,266 @@
+//! Multi-miner aggregation proxy: a lightweight HTTP server that speaks the
+//! same Signum mining API (`/burst?requestType=getMiningInfo`/`submitNonce`)
+//! downstream-facing that `com::client::Client` speaks to a pool upstream.
+//! It caches the most recent `MiningInfoResponse` and fans it back out to
+//! every downstream miner, forwards only the best (lowest) deadline seen per
+//! account upstream through the shared `RequestHandler` -- so the whole farm
+//! rides one upstream connection and one retry/backoff pipeline -- and rolls
+//! every downstream miner's reported capacity into the `total_size_gb` sent
+//! upstream via `RequestHandler::update_capacity`. Analogous to a stratum
+//! proxy collapsing many workers onto one upstream session.
+
+use crate::com::api::MiningInfoResponse;
+use crate::poc_hashing::decode_gensig;
+use crate::requests::RequestHandler;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+#[cfg(feature = "async_io")]
+use tokio::sync::Mutex;
+#[cfg(not(feature = "async_io"))]
+use std::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The best (lowest) deadline reported so far by any downstream miner for
+/// one account at the current height, so repeat submissions for the same
+/// account don't all get forwarded upstream.
+#[derive(Clone, Copy, Debug)]
+struct BestDeadline {
+    height: u64,
+    deadline: u64,
+}
+
+/// Shared state for the downstream-facing proxy server, held behind an
+/// `Arc` so each accepted connection gets its own handle rather than
+/// fighting over a lock for the whole request lifecycle.
+pub struct Proxy {
+    request_handler: Arc<Mutex<RequestHandler>>,
+    /// The mining info currently served downstream, alongside the
+    /// `block_generation` counter `Miner` assigned it -- the same counter
+    /// `RequestHandler::submit_nonce`'s native-mining callers pass as
+    /// `block`, not the pool's blockchain `height`. Forwarded submissions
+    /// must use this one too, or `SubmissionParameters`'s `Ord` compares two
+    /// incomparable counters whenever native mining and the proxy share a
+    /// `RequestHandler`.
+    cached_mining_info: Arc<Mutex<Option<(MiningInfoResponse, u64)>>>,
+    best_deadlines: Arc<Mutex<HashMap<u64, BestDeadline>>>,
+    capacity_by_peer: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl Proxy {
+    pub fn new(request_handler: Arc<Mutex<RequestHandler>>) -> Self {
+        Proxy {
+            request_handler,
+            cached_mining_info: Arc::new(Mutex::new(None)),
+            best_deadlines: Arc::new(Mutex::new(HashMap::new())),
+            capacity_by_peer: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Replaces the mining info served to downstream miners and clears
+    /// last block's best-deadline tracking. Called from the same
+    /// poll/long-poll/pubsub tasks that already drive the miner's own
+    /// reader whenever they see a new block, with `block` being that same
+    /// call's `State.block` generation counter (see `cached_mining_info`).
+    pub async fn update_mining_info(&self, info: MiningInfoResponse, block: u64) {
+        #[cfg(feature = "async_io")]
+        {
+            *self.cached_mining_info.lock().await = Some((info, block));
+            self.best_deadlines.lock().await.clear();
+        }
+        #[cfg(not(feature = "async_io"))]
+        {
+            *self.cached_mining_info.lock().unwrap() = Some((info, block));
+            self.best_deadlines.lock().unwrap().clear();
+        }
+    }
+
+    /// Sums every downstream miner's last-reported capacity and pushes the
+    /// total upstream, the same way the miner would report its own plot
+    /// size. Meant to be called on a fixed interval (see `miner::run`)
+    /// rather than per-request, since capacity changes slowly.
+    pub async fn refresh_upstream_capacity(&self) {
+        #[cfg(feature = "async_io")]
+        let total: usize = self.capacity_by_peer.lock().await.values().sum();
+        #[cfg(not(feature = "async_io"))]
+        let total: usize = self.capacity_by_peer.lock().unwrap().values().sum();
+
+        #[cfg(feature = "async_io")]
+        self.request_handler.lock().await.update_capacity(total).await;
+        #[cfg(not(feature = "async_io"))]
+        self.request_handler.lock().unwrap().update_capacity(total);
+    }
+
+    /// Binds `addr` and serves downstream miners until the process exits.
+    pub async fn run(self: Arc<Self>, addr: SocketAddr) {
+        let listener = match TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("proxy: could not bind to {}: {}", addr, e);
+                return;
+            }
+        };
+        info!("→ Mining proxy listening on http://{}", addr);
+
+        loop {
+            let (socket, peer) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("proxy: accept failed: {}", e);
+                    continue;
+                }
+            };
+            let proxy = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = proxy.handle_connection(socket, peer).await {
+                    warn!("proxy: connection from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(&self, mut socket: TcpStream, peer: SocketAddr) -> std::io::Result<()> {
+        let mut buf = [0u8; 4096];
+        let n = socket.read(&mut buf).await?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let (target, headers) = parse_request(&request);
+        let params = parse_query(target.split('?').nth(1).unwrap_or(""));
+
+        let body = match params.get("requestType").map(String::as_str) {
+            Some("getMiningInfo") => {
+                self.record_capacity(peer.ip(), &headers);
+                self.render_mining_info().await
+            }
+            Some("submitNonce") => self.forward_submission(&params, &headers).await,
+            _ => r#"{"errorCode":1,"errorDescription":"unknown requestType"}"#.to_owned(),
+        };
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await?;
+        Ok(())
+    }
+
+    fn record_capacity(&self, peer: IpAddr, headers: &HashMap<String, String>) {
+        if let Some(capacity) = headers.get("x-capacity").and_then(|v| v.parse::<usize>().ok()) {
+            #[cfg(feature = "async_io")]
+            {
+                // `record_capacity` itself stays sync so callers don't need
+                // to await it; under `async_io` the map is a `tokio` `Mutex`,
+                // whose `blocking_lock` panics when called from inside the
+                // runtime -- spawn the (tiny, uncontended) update instead.
+                let capacity_by_peer = self.capacity_by_peer.clone();
+                tokio::spawn(async move {
+                    capacity_by_peer.lock().await.insert(peer, capacity);
+                });
+            }
+            #[cfg(not(feature = "async_io"))]
+            {
+                self.capacity_by_peer.lock().unwrap().insert(peer, capacity);
+            }
+        }
+    }
+
+    async fn render_mining_info(&self) -> String {
+        #[cfg(feature = "async_io")]
+        let cached = self.cached_mining_info.lock().await.clone();
+        #[cfg(not(feature = "async_io"))]
+        let cached = self.cached_mining_info.lock().unwrap().clone();
+
+        match cached {
+            Some((info, _block)) => format!(
+                r#"{{"height":{},"baseTarget":"{}","generationSignature":"{}","targetDeadline":{}}}"#,
+                info.height, info.base_target, info.generation_signature, info.target_deadline
+            ),
+            None => r#"{"errorCode":2,"errorDescription":"no mining info yet"}"#.to_owned(),
+        }
+    }
+
+    async fn forward_submission(&self, params: &HashMap<String, String>, headers: &HashMap<String, String>) -> String {
+        let parsed = (|| {
+            let account_id: u64 = params.get("accountId")?.parse().ok()?;
+            let nonce: u64 = params.get("nonce")?.parse().ok()?;
+            let height: u64 = params.get("blockheight")?.parse().ok()?;
+            // Mirrors `Client::submit_nonce`: the adjusted deadline travels
+            // in the `X-Deadline` header, with the unadjusted one (if any)
+            // in the query string.
+            let deadline: u64 = headers
+                .get("x-deadline")
+                .and_then(|v| v.parse().ok())
+                .or_else(|| params.get("deadline").and_then(|v| v.parse().ok()))?;
+            Some((account_id, nonce, height, deadline))
+        })();
+
+        let Some((account_id, nonce, height, deadline)) = parsed else {
+            return r#"{"errorCode":1,"errorDescription":"malformed submitNonce request"}"#.to_owned();
+        };
+
+        let is_best = {
+            #[cfg(feature = "async_io")]
+            let mut best_deadlines = self.best_deadlines.lock().await;
+            #[cfg(not(feature = "async_io"))]
+            let mut best_deadlines = self.best_deadlines.lock().unwrap();
+
+            match best_deadlines.get(&account_id) {
+                Some(best) if best.height == height && best.deadline <= deadline => false,
+                _ => {
+                    best_deadlines.insert(account_id, BestDeadline { height, deadline });
+                    true
+                }
+            }
+        };
+
+        if is_best {
+            #[cfg(feature = "async_io")]
+            let cached = self.cached_mining_info.lock().await.clone();
+            #[cfg(not(feature = "async_io"))]
+            let cached = self.cached_mining_info.lock().unwrap().clone();
+
+            let Some((info, block)) = cached else {
+                return r#"{"errorCode":2,"errorDescription":"no mining info yet"}"#.to_owned();
+            };
+            let gen_sig = decode_gensig(&info.generation_signature);
+
+            #[cfg(feature = "async_io")]
+            let request_handler = self.request_handler.lock().await;
+            #[cfg(not(feature = "async_io"))]
+            let request_handler = self.request_handler.lock().unwrap();
+
+            request_handler.submit_nonce(account_id, nonce, height, block, deadline, deadline, gen_sig);
+        }
+
+        format!(r#"{{"result":"success","deadline":{}}}"#, deadline)
+    }
+}
+
+/// Splits an HTTP request into its target (path + query) and a lowercased
+/// header map; the body is ignored since every request this proxy serves
+/// (`getMiningInfo`/`submitNonce`) puts everything it needs in the request
+/// line and headers.
+fn parse_request(raw: &str) -> (&str, HashMap<String, String>) {
+    let mut lines = raw.lines();
+    let target = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("");
+
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_owned()))
+        .collect();
+
+    (target, headers)
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect()
+}
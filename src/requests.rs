@@ -1,17 +1,73 @@
 use crate::com::api::{FetchError, MiningInfoResponse};
 use crate::com::client::{Client, ProxyDetails, SubmissionParameters};
-use crate::future::prio_retry::PrioRetry;
-use futures_util::stream::{StreamExt};
+use crate::future::prio_retry::{Backoff, PrioRetry};
+use crate::histogram::{Histogram, HistogramSnapshot};
+use futures_util::stream::{self, Stream, StreamExt};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "async_io")]
+use tokio::sync::Mutex;
+#[cfg(not(feature = "async_io"))]
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use url::Url;
 
+/// How `RequestHandler` picks among several configured pools.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolStrategy {
+    /// Always prefer the highest-priority (first configured) reachable pool,
+    /// falling back to the next one on failure and returning to it once its
+    /// `get_mining_info` succeeds again.
+    Failover,
+    /// Advance to the next pool on each new block height.
+    RoundRobin,
+    /// Distribute nonce submissions across pools by configured integer weight,
+    /// always picking the pool whose `submitted / weight` ratio is lowest.
+    LoadBalance,
+}
+
+/// Configuration for a single pool in a `RequestHandler` pool list.
 #[derive(Clone)]
-pub struct RequestHandler {
+pub struct PoolConfig {
+    pub base_uri: Url,
+    /// Backup endpoints for this same pool, tried in order (and
+    /// automatically failed back from) by `Client` if `base_uri` becomes
+    /// unreachable. Empty unless the config file lists any under this
+    /// pool's `backup_urls`.
+    pub backup_uris: Vec<Url>,
+    pub secret_phrases: HashMap<u64, String>,
+    pub weight: u32,
+}
+
+struct Pool {
     client: Client,
     tx_submit_data: mpsc::UnboundedSender<SubmissionParameters>,
+    submitted: AtomicUsize,
+    weight: u32,
+}
+
+#[derive(Clone)]
+pub struct RequestHandler {
+    pools: Arc<Vec<Pool>>,
+    strategy: PoolStrategy,
+    /// Index into `pools` of the pool mining info is currently being served
+    /// from, and that submissions for the active block are routed to (under
+    /// `RoundRobin`, already one past the pool that reported the current
+    /// block, see `get_mining_info`).
+    active: Arc<AtomicUsize>,
+    last_height: Arc<AtomicUsize>,
+    /// Round-trip time of every `submit_nonce` HTTP call, across all pools
+    /// and including retries, so operators can see submission latency
+    /// distribution rather than just pass/fail per submission.
+    submit_latency: Arc<Histogram>,
+    /// Mirrors the height of the last `MiningInfoResponse` seen by either
+    /// `get_mining_info` or `get_mining_info_longpoll`, so the submission
+    /// retry loop can tell a stale submission apart from one still worth
+    /// retrying without needing to go through `miner::State`.
+    current_height: Arc<AtomicU64>,
 }
 
 impl RequestHandler {
@@ -23,50 +79,127 @@ impl RequestHandler {
         send_proxy_details: bool,
         additional_headers: HashMap<String, String>,
         handle: tokio::runtime::Handle,
+        max_submit_retries: u32,
+    ) -> RequestHandler {
+        RequestHandler::with_pools(
+            vec![PoolConfig {
+                base_uri,
+                backup_uris: Vec::new(),
+                secret_phrases,
+                weight: 1,
+            }],
+            PoolStrategy::Failover,
+            timeout,
+            total_size_gb,
+            send_proxy_details,
+            additional_headers,
+            handle,
+            max_submit_retries,
+        )
+    }
+
+    pub fn with_pools(
+        pool_configs: Vec<PoolConfig>,
+        strategy: PoolStrategy,
+        timeout: u64,
+        total_size_gb: usize,
+        send_proxy_details: bool,
+        additional_headers: HashMap<String, String>,
+        handle: tokio::runtime::Handle,
+        max_submit_retries: u32,
     ) -> RequestHandler {
+        assert!(!pool_configs.is_empty(), "at least one pool must be configured");
+
         let proxy_details = if send_proxy_details {
             ProxyDetails::Enabled
         } else {
             ProxyDetails::Disabled
         };
 
-        let client = Client::new(
-            base_uri,
-            secret_phrases,
-            timeout,
-            total_size_gb,
-            proxy_details,
-            additional_headers,
-        );
+        let submit_latency = Arc::new(Histogram::new());
+        let current_height = Arc::new(AtomicU64::new(0));
 
-        let (tx_submit_data, rx_submit_nonce_data) = mpsc::unbounded_channel();
-        RequestHandler::handle_submissions(
-            client.clone(),
-            rx_submit_nonce_data,
-            tx_submit_data.clone(),
-            handle,
-        );
+        let pools = pool_configs
+            .into_iter()
+            .map(|pool_cfg| {
+                let mut endpoints = vec![pool_cfg.base_uri];
+                endpoints.extend(pool_cfg.backup_uris);
+                let client = Client::with_endpoints(
+                    endpoints,
+                    pool_cfg.secret_phrases,
+                    timeout,
+                    total_size_gb,
+                    proxy_details.clone(),
+                    additional_headers.clone(),
+                );
+
+                let (tx_submit_data, rx_submit_nonce_data) = mpsc::unbounded_channel();
+                RequestHandler::handle_submissions(
+                    client.clone(),
+                    rx_submit_nonce_data,
+                    tx_submit_data.clone(),
+                    handle.clone(),
+                    submit_latency.clone(),
+                    current_height.clone(),
+                    max_submit_retries,
+                );
+
+                Pool {
+                    client,
+                    tx_submit_data,
+                    submitted: AtomicUsize::new(0),
+                    weight: pool_cfg.weight.max(1),
+                }
+            })
+            .collect();
 
         RequestHandler {
-            client,
-            tx_submit_data,
+            pools: Arc::new(pools),
+            strategy,
+            active: Arc::new(AtomicUsize::new(0)),
+            last_height: Arc::new(AtomicUsize::new(0)),
+            submit_latency,
+            current_height,
         }
     }
 
+    /// Starting delay for a retried submission; doubled on every further
+    /// retry of the same `(account_id, height, deadline)` up to
+    /// `SUBMIT_RETRY_MAX_DELAY`.
+    const SUBMIT_RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+    const SUBMIT_RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+    /// A long-poll reply that arrives within this long of the request being
+    /// sent is treated as the pool answering immediately, see
+    /// `subscribe_mining_info`.
+    const FAST_REPLY_THRESHOLD: Duration = Duration::from_millis(500);
+    /// Consecutive fast replies before `subscribe_mining_info` gives up on
+    /// the active pool supporting long-polling and ends the stream.
+    const MAX_CONSECUTIVE_FAST_REPLIES: u32 = 3;
+
     fn handle_submissions(
         client: Client,
         rx: mpsc::UnboundedReceiver<SubmissionParameters>,
         tx_submit_data: mpsc::UnboundedSender<SubmissionParameters>,
         handle: tokio::runtime::Handle,
+        submit_latency: Arc<Histogram>,
+        current_height: Arc<AtomicU64>,
+        max_submit_retries: u32,
     ) {
         handle.spawn(async move {
             let wrapped_rx = UnboundedReceiverStream::new(rx);
-            let stream = PrioRetry::new(wrapped_rx, Duration::from_secs(3));
+            let backoff = Backoff::new(Duration::from_secs(3), Duration::from_secs(60), true);
+            let stream = PrioRetry::new(wrapped_rx, backoff);
 
             let mut stream = Box::pin(stream);
             while let Some(submission_params) = stream.as_mut().next().await {
                 let tx_submit_data = tx_submit_data.clone();
-                let result = client.clone().submit_nonce(&submission_params).await;
+                let started_at = Instant::now();
+                // `submit_nonce` only needs `&self`: reusing `client` directly
+                // (instead of cloning it per submission) keeps every
+                // submission on the same pooled `reqwest::Client` connections.
+                let result = client.submit_nonce(&submission_params).await;
+                let elapsed_ms = started_at.elapsed().as_millis() as u64;
+                submit_latency.record(elapsed_ms);
 
                 match result {
                     Ok(res) => {
@@ -93,9 +226,12 @@ impl RequestHandler {
                                 submission_params.nonce,
                                 submission_params.deadline,
                             );
-                            if tx_submit_data.send(submission_params).is_err() {
-                                error!("can't send submission params");
-                            }
+                            Self::retry_or_abandon(
+                                submission_params,
+                                &current_height,
+                                &tx_submit_data,
+                                max_submit_retries,
+                            );
                         } else {
                             log_submission_not_accepted(
                                 submission_params.height,
@@ -114,8 +250,21 @@ impl RequestHandler {
                             submission_params.deadline,
                             &x.to_string(),
                         );
-                        if tx_submit_data.send(submission_params).is_err() {
-                            error!("can't send submission params");
+                        if Self::is_retryable_http_error(&x) {
+                            Self::retry_or_abandon(
+                                submission_params,
+                                &current_height,
+                                &tx_submit_data,
+                                max_submit_retries,
+                            );
+                        } else {
+                            error!(
+                                "submission abandoned, non-retryable error: account={}, nonce={}, deadline={}, outstanding for {:?}",
+                                submission_params.account_id,
+                                submission_params.nonce,
+                                submission_params.deadline,
+                                submission_params.first_seen.elapsed(),
+                            );
                         }
                     }
                 }
@@ -123,8 +272,220 @@ impl RequestHandler {
         });
     }
 
-    pub fn get_mining_info<'a>(&'a self) -> impl std::future::Future<Output = Result<MiningInfoResponse, FetchError>> + 'a {
-        self.client.get_mining_info()
+    /// Whitelists the `FetchError::Http` messages worth retrying: connection
+    /// failures, timeouts, and 5xx responses are transient; anything else
+    /// (a malformed request, a 4xx, ...) won't get better by resending it.
+    fn is_retryable_http_error(message: &str) -> bool {
+        let message = message.to_ascii_lowercase();
+        message.contains("timed out")
+            || message.contains("timeout")
+            || message.contains("connect")
+            || message.contains("connection")
+            || ["500", "502", "503", "504"]
+                .iter()
+                .any(|code| message.contains(code))
+    }
+
+    /// Resends `submission_params` with exponential backoff as long as its
+    /// height is still the one mining is currently happening for and it
+    /// hasn't already exhausted `max_submit_retries`; otherwise abandons it
+    /// (it can no longer be confirmed against anything, or has retried
+    /// enough that further attempts are unlikely to help). Bumps its own
+    /// `attempt` count when it resends, and reports how long it has been
+    /// outstanding either way.
+    fn retry_or_abandon(
+        mut submission_params: SubmissionParameters,
+        current_height: &Arc<AtomicU64>,
+        tx_submit_data: &mpsc::UnboundedSender<SubmissionParameters>,
+        max_submit_retries: u32,
+    ) {
+        let outstanding = submission_params.first_seen.elapsed();
+
+        let mining_height = current_height.load(Ordering::SeqCst);
+        if mining_height != 0 && mining_height != submission_params.height {
+            log_submission_abandoned(
+                submission_params.account_id,
+                submission_params.nonce,
+                submission_params.deadline,
+                outstanding,
+            );
+            return;
+        }
+
+        if submission_params.attempt >= max_submit_retries {
+            warn!(
+                "giving up on submission after {} attempts, outstanding for {:?}: account={}, nonce={}, deadline={}",
+                submission_params.attempt,
+                outstanding,
+                submission_params.account_id,
+                submission_params.nonce,
+                submission_params.deadline,
+            );
+            return;
+        }
+
+        let attempt = submission_params.attempt;
+        submission_params.attempt += 1;
+        let delay = Self::SUBMIT_RETRY_BASE_DELAY
+            .saturating_mul(1u32 << attempt.min(16))
+            .min(Self::SUBMIT_RETRY_MAX_DELAY);
+
+        warn!(
+            "{: <80}",
+            format!(
+                "submission retry #{} scheduled in {:?}, outstanding for {:?}: account={}, nonce={}, deadline={}",
+                attempt + 1,
+                delay,
+                outstanding,
+                submission_params.account_id,
+                submission_params.nonce,
+                submission_params.deadline,
+            )
+        );
+
+        let tx_submit_data = tx_submit_data.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if tx_submit_data.send(submission_params).is_err() {
+                error!("can't send submission params");
+            }
+        });
+    }
+
+    /// Fetches mining info from the pool the `PoolStrategy` currently prefers.
+    ///
+    /// `Failover` always attempts the highest-priority pool (index 0) first and
+    /// walks down the list on error, so it returns to the primary as soon as it
+    /// recovers. `RoundRobin` and `LoadBalance` continue from whichever pool is
+    /// currently active, falling over to the next one only if it is unreachable.
+    /// Whichever pool answers becomes the active pool that submissions for this
+    /// block are routed to, except under `RoundRobin`: a poll that reports a new
+    /// height moves `active` on to the next pool straight away, so submissions
+    /// for that block go to the pool after the one that reported it.
+    pub async fn get_mining_info(&self) -> Result<MiningInfoResponse, FetchError> {
+        let pool_count = self.pools.len();
+        let mut idx = if self.strategy == PoolStrategy::Failover {
+            0
+        } else {
+            self.active.load(Ordering::SeqCst) % pool_count
+        };
+
+        let mut last_err = None;
+        for _ in 0..pool_count {
+            match self.pools[idx].client.get_mining_info().await {
+                Ok(info) => {
+                    self.active.store(idx, Ordering::SeqCst);
+                    self.current_height.store(info.height, Ordering::SeqCst);
+
+                    if self.strategy == PoolStrategy::RoundRobin {
+                        let prev_height = self.last_height.swap(info.height as usize, Ordering::SeqCst);
+                        let next_active =
+                            Self::round_robin_next_active(idx, pool_count, prev_height, info.height as usize);
+                        self.active.store(next_active, Ordering::SeqCst);
+                    }
+
+                    return Ok(info);
+                }
+                Err(e) => {
+                    warn!("pool #{} unreachable, trying next pool in the list", idx);
+                    last_err = Some(e);
+                    idx = (idx + 1) % pool_count;
+                }
+            }
+        }
+
+        Err(last_err.expect("pool_count > 0"))
+    }
+
+    /// Computes the pool that should be active for the *next* `RoundRobin`
+    /// poll, given the pool that just answered, the previous height on
+    /// record (0 if this is the first poll ever) and the height it just
+    /// reported. Pulled out of `get_mining_info` as a pure function so the
+    /// "first poll, don't rotate" and "height unchanged, don't rotate"
+    /// edge cases can be exercised directly.
+    fn round_robin_next_active(idx: usize, pool_count: usize, prev_height: usize, new_height: usize) -> usize {
+        if prev_height != 0 && prev_height != new_height {
+            (idx + 1) % pool_count
+        } else {
+            idx
+        }
+    }
+
+    /// Long-poll variant of `get_mining_info`, routed through the same pool
+    /// selection as the regular poll so submissions keep going to whichever pool
+    /// answered. Unlike `get_mining_info`, a pool that doesn't support long-poll
+    /// just answers right away as if to a plain poll rather than erroring, so
+    /// there is no dedicated failover loop here: the caller already re-invokes
+    /// this in a loop and will naturally pick up a pool swap on the next round.
+    pub async fn get_mining_info_longpoll(
+        &self,
+        last_generation_signature: &str,
+        wait_timeout: Duration,
+    ) -> Result<MiningInfoResponse, FetchError> {
+        let idx = if self.strategy == PoolStrategy::Failover {
+            0
+        } else {
+            self.active.load(Ordering::SeqCst) % self.pools.len()
+        };
+
+        let result = self.pools[idx]
+            .client
+            .get_mining_info_longpoll(last_generation_signature, wait_timeout)
+            .await;
+        if let Ok(info) = &result {
+            self.current_height.store(info.height, Ordering::SeqCst);
+        }
+        result
+    }
+
+    /// Streams `MiningInfoResponse`s by repeatedly calling
+    /// [`Self::get_mining_info_longpoll`], so the miner driver gets a new
+    /// item the instant the active pool pushes a changed block instead of
+    /// waiting on its own fixed `Interval`. If the active pool doesn't
+    /// actually support long-polling — every call answers right away
+    /// regardless of `wait_timeout` — the stream ends after
+    /// `MAX_CONSECUTIVE_FAST_REPLIES` such replies in a row, so the caller
+    /// can fall back to `Interval`-driven polling instead of hammering the
+    /// pool with back-to-back requests.
+    pub fn subscribe_mining_info(
+        &self,
+        wait_timeout: Duration,
+    ) -> impl Stream<Item = Result<MiningInfoResponse, FetchError>> + '_ {
+        let initial = (String::new(), 0u32);
+        stream::unfold(initial, move |(last_generation_signature, fast_replies)| async move {
+            let started = Instant::now();
+            let result = self.get_mining_info_longpoll(&last_generation_signature, wait_timeout).await;
+            let responded_fast = started.elapsed() < Self::FAST_REPLY_THRESHOLD;
+
+            let (next_generation_signature, fast_replies) = match &result {
+                Ok(info) => (
+                    info.generation_signature.clone(),
+                    if responded_fast { fast_replies + 1 } else { 0 },
+                ),
+                Err(_) => (last_generation_signature, fast_replies),
+            };
+
+            if fast_replies >= Self::MAX_CONSECUTIVE_FAST_REPLIES {
+                return None;
+            }
+
+            Some((result, (next_generation_signature, fast_replies)))
+        })
+    }
+
+    /// Picks the pool whose `submitted / weight` ratio is lowest, spreading load
+    /// across the configured pools proportionally to their weight.
+    fn pick_load_balanced_pool(&self) -> usize {
+        self.pools
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let ratio_a = a.submitted.load(Ordering::SeqCst) as f64 / a.weight as f64;
+                let ratio_b = b.submitted.load(Ordering::SeqCst) as f64 / b.weight as f64;
+                ratio_a.partial_cmp(&ratio_b).expect("ratios are never NaN")
+            })
+            .map(|(i, _)| i)
+            .expect("at least one pool is always configured")
     }
 
     pub fn submit_nonce(
@@ -137,7 +498,18 @@ impl RequestHandler {
         deadline: u64,
         gen_sig: [u8; 32],
     ) {
-        let res = self.tx_submit_data.send(SubmissionParameters {
+        // `LoadBalance` spreads submissions across every pool; the other
+        // strategies always submit to the block's active pool.
+        let idx = match self.strategy {
+            PoolStrategy::LoadBalance => self.pick_load_balanced_pool(),
+            PoolStrategy::Failover | PoolStrategy::RoundRobin => {
+                self.active.load(Ordering::SeqCst) % self.pools.len()
+            }
+        };
+        let pool = &self.pools[idx];
+        pool.submitted.fetch_add(1, Ordering::SeqCst);
+
+        let res = pool.tx_submit_data.send(SubmissionParameters {
             account_id,
             nonce,
             height,
@@ -145,20 +517,134 @@ impl RequestHandler {
             deadline_unadjusted,
             deadline,
             gen_sig,
+            attempt: 0,
+            first_seen: Instant::now(),
         });
         if let Err(e) = res {
             error!("can't send submission params: {}", e);
         }
     }
 
+    /// Snapshot of the submit round-trip-time distribution across every
+    /// pool, for a future metrics endpoint (or the round-finished log line)
+    /// to scrape without needing to touch the submission path itself.
+    #[cfg(feature = "async_io")]
+    pub async fn submit_latency_snapshot(&self) -> HistogramSnapshot {
+        self.submit_latency.snapshot()
+    }
+
+    #[cfg(not(feature = "async_io"))]
+    pub fn submit_latency_snapshot(&self) -> HistogramSnapshot {
+        self.submit_latency.snapshot()
+    }
+
+    /// Resolves the pool list a `Cfg` describes: its explicit `pools` list if
+    /// one was configured, or the legacy single `url`/
+    /// `account_id_to_secret_phrase` pair otherwise, so older config files
+    /// keep working unchanged. Shared by `Miner::new` and the SIGHUP reload
+    /// path in `main` so both build the pool list the same way.
+    pub fn resolve_pools(
+        pools: Vec<PoolConfig>,
+        legacy_url: Url,
+        legacy_secret_phrases: HashMap<u64, String>,
+    ) -> Vec<PoolConfig> {
+        if pools.is_empty() {
+            vec![PoolConfig {
+                base_uri: legacy_url,
+                backup_uris: Vec::new(),
+                secret_phrases: legacy_secret_phrases,
+                weight: 1,
+            }]
+        } else {
+            pools
+        }
+    }
+
+    /// Rebuilds the pool list from a freshly re-read config and atomically
+    /// swaps it into `request_handler`. Submissions already in flight
+    /// against the old pools are unaffected: their retry tasks keep running
+    /// against the `Client`s they were handed until they resolve, since
+    /// nothing holds on to the old `RequestHandler` but them; only
+    /// subsequently routed work picks up the reloaded list. Used by `main`'s
+    /// SIGHUP handler so operators can add or drain a pool without losing
+    /// in-flight submissions or restarting the miner.
+    #[cfg(feature = "async_io")]
+    pub async fn reload(
+        request_handler: &Arc<Mutex<RequestHandler>>,
+        pools: Vec<PoolConfig>,
+        strategy: PoolStrategy,
+        timeout: u64,
+        total_size_gb: usize,
+        send_proxy_details: bool,
+        additional_headers: HashMap<String, String>,
+        handle: tokio::runtime::Handle,
+        max_submit_retries: u32,
+    ) {
+        let new_handler = RequestHandler::with_pools(
+            pools,
+            strategy,
+            timeout,
+            total_size_gb,
+            send_proxy_details,
+            additional_headers,
+            handle,
+            max_submit_retries,
+        );
+        *request_handler.lock().await = new_handler;
+    }
+
+    #[cfg(not(feature = "async_io"))]
+    pub fn reload(
+        request_handler: &Arc<Mutex<RequestHandler>>,
+        pools: Vec<PoolConfig>,
+        strategy: PoolStrategy,
+        timeout: u64,
+        total_size_gb: usize,
+        send_proxy_details: bool,
+        additional_headers: HashMap<String, String>,
+        handle: tokio::runtime::Handle,
+        max_submit_retries: u32,
+    ) {
+        let new_handler = RequestHandler::with_pools(
+            pools,
+            strategy,
+            timeout,
+            total_size_gb,
+            send_proxy_details,
+            additional_headers,
+            handle,
+            max_submit_retries,
+        );
+        *request_handler.lock().unwrap() = new_handler;
+    }
+
+    /// Prometheus text exposition of every pool's `get_mining_info`/
+    /// `submit_nonce` latency and result counters, tagged by pool index, for
+    /// the metrics HTTP endpoint in `miner::run` to serve.
+    pub fn render_metrics(&self) -> String {
+        self.pools
+            .iter()
+            .enumerate()
+            .map(|(idx, pool)| pool.client.metrics().render_prometheus(&format!("pool{idx}")))
+            .collect()
+    }
+
+    // `Client::update_capacity` mutates its header map through an inner
+    // `Arc<Mutex<..>>`, so updating a clone updates every pool's shared state
+    // without needing mutable access to the `Pool`s themselves (and thus
+    // without needing to clone the whole pool list out of its `Arc`).
     #[cfg(feature = "async_io")]
     pub async fn update_capacity(&mut self, total_size_gb: usize) {
-        self.client.update_capacity(total_size_gb).await;
+        for pool in self.pools.iter() {
+            pool.client.clone().update_capacity(total_size_gb).await;
+        }
     }
 
     #[cfg(not(feature = "async_io"))]
     pub fn update_capacity(&mut self, total_size_gb: usize) {
-        self.client.update_capacity(total_size_gb);
+        for pool in self.pools.iter() {
+            pool.client.clone().update_capacity(total_size_gb);
+        }
     }
 }
 
@@ -215,6 +701,16 @@ fn log_pool_busy(account_id: u64, nonce: u64, deadline: u64) {
     );
 }
 
+fn log_submission_abandoned(account_id: u64, nonce: u64, deadline: u64, outstanding: Duration) {
+    error!(
+        "{: <80}",
+        format!(
+            "submission abandoned, a new block arrived: account={}, nonce={}, deadline={}, outstanding for {:?}",
+            account_id, nonce, deadline, outstanding
+        )
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,8 +736,92 @@ mod tests {
         true,
         HashMap::new(),
         handle,
+        3,
     );
 
     request_handler.submit_nonce(1337, 12, 111, 0, 7123, 1193, [0; 32]);
 }
+
+    fn test_pool_configs(weights: &[u32]) -> Vec<PoolConfig> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, &weight)| PoolConfig {
+                base_uri: format!("{}/{}", BASE_URL, i).parse().expect("invalid URL"),
+                backup_uris: Vec::new(),
+                secret_phrases: HashMap::new(),
+                weight,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pick_load_balanced_pool_prefers_lowest_submitted_ratio() {
+        let rt = Runtime::new().expect("can't create runtime");
+        let handle = rt.handle().clone();
+
+        let request_handler = RequestHandler::with_pools(
+            test_pool_configs(&[1, 3]),
+            PoolStrategy::LoadBalance,
+            3,
+            12,
+            true,
+            HashMap::new(),
+            handle,
+            3,
+        );
+
+        // Nothing submitted yet: both ratios are 0, a tie, and the first
+        // pool (lowest index) wins ties.
+        assert_eq!(request_handler.pick_load_balanced_pool(), 0);
+
+        // Pool #0 (weight 1) has taken a submission, pool #1 (weight 3) none:
+        // ratio_a=1/1=1 > ratio_b=0/3=0, so #1 is now the lower ratio.
+        request_handler.pools[0].submitted.store(1, Ordering::SeqCst);
+        assert_eq!(request_handler.pick_load_balanced_pool(), 1);
+
+        // Pool #1 has caught up proportionally (3 submitted / weight 3 == 1
+        // submitted / weight 1), so it's a tie again and #0 wins.
+        request_handler.pools[1].submitted.store(3, Ordering::SeqCst);
+        assert_eq!(request_handler.pick_load_balanced_pool(), 0);
+
+        // Pool #1 overshoots its proportional share: #0 becomes the lower
+        // ratio (2/1=2 < 9/3=3).
+        request_handler.pools[1].submitted.store(9, Ordering::SeqCst);
+        request_handler.pools[0].submitted.store(2, Ordering::SeqCst);
+        assert_eq!(request_handler.pick_load_balanced_pool(), 0);
+    }
+
+    #[test]
+    fn test_round_robin_next_active_first_poll_does_not_rotate() {
+        // `prev_height == 0` means this is the first poll ever; nothing to
+        // compare against yet, so stay on the pool that just answered.
+        assert_eq!(RequestHandler::round_robin_next_active(0, 3, 0, 100), 0);
+    }
+
+    #[test]
+    fn test_round_robin_next_active_same_height_does_not_rotate() {
+        assert_eq!(RequestHandler::round_robin_next_active(1, 3, 100, 100), 1);
+    }
+
+    #[test]
+    fn test_round_robin_next_active_height_change_rotates_and_wraps() {
+        assert_eq!(RequestHandler::round_robin_next_active(0, 3, 100, 101), 1);
+        assert_eq!(RequestHandler::round_robin_next_active(2, 3, 100, 101), 0);
+    }
+
+    #[test]
+    fn test_is_retryable_http_error_whitelist() {
+        assert!(RequestHandler::is_retryable_http_error("operation timed out"));
+        assert!(RequestHandler::is_retryable_http_error("Connection refused"));
+        assert!(RequestHandler::is_retryable_http_error("HTTP error 503 Service Unavailable"));
+        assert!(RequestHandler::is_retryable_http_error("502 Bad Gateway"));
+    }
+
+    #[test]
+    fn test_is_retryable_http_error_rejects_non_transient_errors() {
+        assert!(!RequestHandler::is_retryable_http_error("HTTP error 400 Bad Request"));
+        assert!(!RequestHandler::is_retryable_http_error("invalid deadline"));
+        assert!(!RequestHandler::is_retryable_http_error(""));
+    }
 }
@@ -0,0 +1,270 @@
+//! Background plot-integrity scrubber.
+//!
+//! Periodically walks every configured plot file, confirms its size still
+//! matches what it was plotted with (`meta.nonces` nonces of
+//! `SCOOPS_PER_NONCE` scoops of `SCOOP_SIZE` bytes each), and
+//! recomputes every nonce's scoop via [`crate::poc_hashing`] to compare
+//! against what's actually on disk -- catching not just truncated files but
+//! the kind of silent bit-rot/bad-sector corruption a size check alone can't
+//! see, which would otherwise only show up later as missed deadlines. It
+//! runs as a single [`crate::worker`]-registered task with a "tranquility"
+//! sleep between plots (and the same sleep, at least 1ms, every
+//! `SCRUB_YIELD_EVERY` nonces inside the per-nonce recompute loop, so a
+//! single large plot can't monopolize the runtime) so it never competes
+//! with live mining reads, and does not start a plot while
+//! `State.scanning` is true. Per-drive progress (when a
+//! drive was last fully scrubbed) is persisted to a small text file so a
+//! restart doesn't throw away that history.
+
+use crate::miner::State;
+use crate::plot::{Plot, SCOOP_SIZE};
+use crate::poc_hashing;
+use crate::worker::{WorkerCommand, WorkerRegistry, WorkerState};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+#[cfg(feature = "async_io")]
+use tokio::sync::Mutex;
+#[cfg(not(feature = "async_io"))]
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long the scrubber sleeps between plot files. Larger values are
+/// gentler on I/O but make a full pass take longer; this mirrors Garage's
+/// scrub "tranquility" knob.
+#[derive(Clone, Copy, Debug)]
+pub struct ScrubTranquility(pub u64);
+
+impl Default for ScrubTranquility {
+    fn default() -> Self {
+        ScrubTranquility(100)
+    }
+}
+
+fn progress_file(progress_dir: &Path) -> PathBuf {
+    progress_dir.join("scrub_progress.txt")
+}
+
+/// Loads `drive_id -> last-completed unix timestamp` from the progress file,
+/// ignoring it entirely (starting fresh) if it's missing or malformed.
+fn load_progress(progress_dir: &Path) -> HashMap<String, u64> {
+    let mut progress = HashMap::new();
+    let Ok(contents) = fs::read_to_string(progress_file(progress_dir)) else {
+        return progress;
+    };
+    for line in contents.lines() {
+        if let Some((drive_id, timestamp)) = line.split_once('\t') {
+            if let Ok(timestamp) = timestamp.parse() {
+                progress.insert(drive_id.to_owned(), timestamp);
+            }
+        }
+    }
+    progress
+}
+
+fn save_progress(progress_dir: &Path, progress: &HashMap<String, u64>) {
+    let mut contents = String::new();
+    for (drive_id, timestamp) in progress {
+        contents.push_str(&format!("{}\t{}\n", drive_id, timestamp));
+    }
+    let path = progress_file(progress_dir);
+    if let Err(e) = fs::write(&path, contents) {
+        warn!("scrub: could not persist progress to {}: {}", path.display(), e);
+    }
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// One log line per contiguous run of nonces whose on-disk scoop didn't
+/// match its recomputed PoC hash, instead of one line per bad nonce.
+fn corrupted_range_msg(path: &Path, first_nonce: u64, last_nonce: u64) -> String {
+    format!(
+        "{}: PoC hash mismatch for nonce range {}..={} ({} nonce(s))",
+        path.display(),
+        first_nonce,
+        last_nonce,
+        last_nonce - first_nonce + 1
+    )
+}
+
+/// Checks a single plot file's size against what it was plotted with, then
+/// reads every nonce's scoop back off disk and compares it against
+/// `poc_hashing::generate_scoop`'s recomputation of what that nonce's scoop
+/// should contain. A mismatch means the bytes on disk are neither what was
+/// plotted nor a truncation -- silent corruption that a size check can't
+/// catch. Yields every `SCRUB_YIELD_EVERY` nonces so one large plot can't
+/// starve the tranquility throttle between plots.
+const SCRUB_YIELD_EVERY: u64 = 64;
+/// Scoops in one nonce; a plot file holds `meta.nonces` nonces of this many
+/// `SCOOP_SIZE`-byte scoops.
+const SCOOPS_PER_NONCE: u64 = 4096;
+
+async fn scrub_plot(plot: &Plot, tranquility: ScrubTranquility) -> Result<(), Vec<String>> {
+    let expected_len = plot.meta.nonces as u64 * SCOOPS_PER_NONCE * SCOOP_SIZE as u64;
+    let mut file =
+        File::open(&plot.path).map_err(|e| vec![format!("{}: {}", plot.path.display(), e)])?;
+    let actual_len = file
+        .metadata()
+        .map_err(|e| vec![format!("{}: {}", plot.path.display(), e)])?
+        .len();
+
+    if actual_len != expected_len {
+        return Err(vec![format!(
+            "{}: size mismatch, expected {} bytes ({} nonces) but found {} bytes",
+            plot.path.display(),
+            expected_len,
+            plot.meta.nonces,
+            actual_len
+        )]);
+    }
+
+    let mut errors = Vec::new();
+    let mut bad_run: Option<(u64, u64)> = None;
+    let mut scoop = vec![0u8; SCOOP_SIZE as usize];
+
+    for nonce_offset in 0..plot.meta.nonces {
+        let nonce = plot.meta.start_nonce + nonce_offset;
+
+        let read_result = file
+            .seek(SeekFrom::Start(nonce_offset as u64 * SCOOP_SIZE as u64))
+            .and_then(|_| file.read_exact(&mut scoop));
+        if let Err(e) = read_result {
+            errors.push(format!(
+                "{}: read error at nonce {}: {}",
+                plot.path.display(),
+                nonce,
+                e
+            ));
+            break;
+        }
+
+        let expected_scoop = poc_hashing::generate_scoop(plot.meta.account_id, nonce);
+        if scoop != expected_scoop {
+            bad_run = Some(match bad_run {
+                Some((first, _)) => (first, nonce),
+                None => (nonce, nonce),
+            });
+        } else if let Some((first, last)) = bad_run.take() {
+            errors.push(corrupted_range_msg(&plot.path, first, last));
+        }
+
+        if nonce_offset > 0 && nonce_offset % SCRUB_YIELD_EVERY == 0 {
+            tokio::time::sleep(Duration::from_millis(tranquility.0.max(1))).await;
+        }
+    }
+    if let Some((first, last)) = bad_run {
+        errors.push(corrupted_range_msg(&plot.path, first, last));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Runs the scrub worker until it receives `WorkerCommand::Cancel`. Intended
+/// to be driven from a single `tokio::spawn` in `Miner::run`.
+pub async fn run_scrub_worker(
+    drive_id_to_plots: HashMap<String, Arc<Vec<Mutex<Plot>>>>,
+    state: Arc<Mutex<State>>,
+    tranquility: ScrubTranquility,
+    progress_dir: PathBuf,
+    registry: WorkerRegistry,
+) {
+    let (worker, mut cmd_rx) = registry.register("plot-scrub").await;
+    let mut paused = false;
+    let mut progress = load_progress(&progress_dir);
+
+    if drive_id_to_plots.is_empty() {
+        warn!("scrub: no plots configured, worker has nothing to do");
+        worker.report(WorkerState::Dead).await;
+        return;
+    }
+
+    loop {
+        while let Ok(cmd) = cmd_rx.try_recv() {
+            match cmd {
+                WorkerCommand::Start => paused = false,
+                WorkerCommand::Pause => paused = true,
+                WorkerCommand::Cancel => {
+                    worker.report(WorkerState::Dead).await;
+                    return;
+                }
+            }
+        }
+
+        if paused {
+            worker.report(WorkerState::Idle).await;
+            tokio::time::sleep(Duration::from_millis(tranquility.0.max(50))).await;
+            continue;
+        }
+
+        #[cfg(feature = "async_io")]
+        let scanning = state.lock().await.scanning;
+        #[cfg(not(feature = "async_io"))]
+        let scanning = state.lock().unwrap().scanning;
+
+        // Yield entirely to live mining I/O; don't even count this as a tick.
+        if scanning {
+            worker.report(WorkerState::Idle).await;
+            tokio::time::sleep(Duration::from_millis(tranquility.0.max(50))).await;
+            continue;
+        }
+
+        // Re-checked before every single plot (not just once per pass)
+        // below, so a live round starting mid-pass preempts scrubbing as
+        // soon as the plot being scrubbed is done, instead of waiting for
+        // the whole drive/plot loop to finish first.
+        let mut preempted = false;
+
+        'drives: for (drive_id, plots) in &drive_id_to_plots {
+            for plot in plots.iter() {
+                #[cfg(feature = "async_io")]
+                let still_scanning = state.lock().await.scanning;
+                #[cfg(not(feature = "async_io"))]
+                let still_scanning = state.lock().unwrap().scanning;
+                if still_scanning {
+                    preempted = true;
+                    break 'drives;
+                }
+
+                #[cfg(feature = "async_io")]
+                let plot = plot.lock().await;
+                #[cfg(not(feature = "async_io"))]
+                let plot = plot.lock().unwrap();
+
+                if let Err(errors) = scrub_plot(&plot, tranquility).await {
+                    for msg in errors {
+                        error!("scrub: corrupted plot detected: {}", msg);
+                    }
+                }
+                drop(plot);
+
+                tokio::time::sleep(Duration::from_millis(tranquility.0)).await;
+            }
+
+            progress.insert(drive_id.clone(), now_unix_secs());
+            save_progress(&progress_dir, &progress);
+            worker.report(WorkerState::Active).await;
+        }
+
+        if preempted {
+            worker.report(WorkerState::Idle).await;
+            tokio::time::sleep(Duration::from_millis(tranquility.0.max(50))).await;
+            continue;
+        }
+
+        // A full pass just finished; rest before starting the next one
+        // rather than immediately hammering every drive again.
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
@@ -0,0 +1,218 @@
+//! Runtime registry for the miner's long-running background tasks (mining
+//! info poll/long-poll/pubsub, capacity refresh, the CPU worker, plot
+//! scrubbing).
+//!
+//! Before this module, every long-running task was a bare `thread::spawn`/
+//! `tokio::spawn` with no way to see whether it was still alive or making
+//! progress short of reading the logs. Each of those tasks now registers a
+//! [`WorkerHandle`] with a shared [`WorkerRegistry`], reports its own state
+//! on every iteration, and listens on a [`WorkerCommand`] channel so an
+//! operator can pause or cancel it without killing the process.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "async_io")]
+use tokio::sync::Mutex;
+#[cfg(not(feature = "async_io"))]
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+/// Observed state of a registered worker, as reported by the task itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerState {
+    /// Did useful work on its last iteration.
+    Active,
+    /// Ran but found nothing to do (e.g. paused, or idling between ticks).
+    Idle,
+    /// Exited, normally or after an unrecoverable error.
+    Dead,
+}
+
+/// Command sent to a worker through its control channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerCommand {
+    /// Resume normal operation after a `Pause`.
+    Start,
+    /// Keep the task alive but have it skip its actual work until `Start`.
+    Pause,
+    /// Stop the task for good.
+    Cancel,
+}
+
+struct RegisteredWorker {
+    name: String,
+    state: Arc<Mutex<WorkerState>>,
+    iterations: Arc<AtomicUsize>,
+    last_error: Arc<Mutex<Option<String>>>,
+    tx_cmd: mpsc::UnboundedSender<WorkerCommand>,
+}
+
+/// Snapshot of a single worker's state, returned by [`WorkerRegistry::status`].
+#[derive(Clone, Debug)]
+pub struct WorkerStatus {
+    pub name: String,
+    pub state: WorkerState,
+    pub iterations: usize,
+    pub last_error: Option<String>,
+}
+
+/// Handle a registered task uses to report its own progress. Cheap to clone
+/// and move into the closures/async blocks that make up the task's loop.
+#[derive(Clone)]
+pub struct WorkerHandle {
+    name: String,
+    state: Arc<Mutex<WorkerState>>,
+    iterations: Arc<AtomicUsize>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl WorkerHandle {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Records the worker's state for this iteration. Call this once per
+    /// loop/tick; `Active`/`Idle` also bump the iteration counter, `Dead`
+    /// does not since there won't be a next one.
+    pub async fn report(&self, state: WorkerState) {
+        #[cfg(feature = "async_io")]
+        {
+            *self.state.lock().await = state;
+        }
+        #[cfg(not(feature = "async_io"))]
+        {
+            *self.state.lock().unwrap() = state;
+        }
+        if state != WorkerState::Dead {
+            self.iterations.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Records the most recent error seen by this worker, without changing
+    /// its reported state (a worker can hit a transient error and stay
+    /// `Active`, or fail for good and report `Dead` separately).
+    pub async fn report_error(&self, err: impl std::fmt::Display) {
+        let err = err.to_string();
+        #[cfg(feature = "async_io")]
+        {
+            *self.last_error.lock().await = Some(err);
+        }
+        #[cfg(not(feature = "async_io"))]
+        {
+            *self.last_error.lock().unwrap() = Some(err);
+        }
+    }
+}
+
+/// Central, clonable registry the miner's controllable background tasks
+/// register with (the reader, GPU and nonce-submission tasks do not).
+/// Lives as long as the `Miner` itself and is cheap to clone (it's an `Arc`
+/// underneath), so it can be handed to every spawned task plus whatever
+/// surfaces the status query (see `miner::run`'s `SIGUSR1` handler).
+#[derive(Clone, Default)]
+pub struct WorkerRegistry {
+    workers: Arc<Mutex<Vec<RegisteredWorker>>>,
+}
+
+impl WorkerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new worker under `name` and returns the handle it should
+    /// report progress through, plus the receiving half of its command
+    /// channel for it to poll (or await on) each iteration.
+    pub async fn register(
+        &self,
+        name: impl Into<String>,
+    ) -> (WorkerHandle, mpsc::UnboundedReceiver<WorkerCommand>) {
+        let name = name.into();
+        let (tx_cmd, rx_cmd) = mpsc::unbounded_channel();
+        let state = Arc::new(Mutex::new(WorkerState::Idle));
+        let iterations = Arc::new(AtomicUsize::new(0));
+        let last_error = Arc::new(Mutex::new(None));
+
+        let registered = RegisteredWorker {
+            name: name.clone(),
+            state: state.clone(),
+            iterations: iterations.clone(),
+            last_error: last_error.clone(),
+            tx_cmd,
+        };
+        #[cfg(feature = "async_io")]
+        self.workers.lock().await.push(registered);
+        #[cfg(not(feature = "async_io"))]
+        self.workers.lock().unwrap().push(registered);
+
+        (
+            WorkerHandle {
+                name,
+                state,
+                iterations,
+                last_error,
+            },
+            rx_cmd,
+        )
+    }
+
+    /// Snapshot of every registered worker's current state, for the status
+    /// query an operator triggers without killing the process.
+    pub async fn status(&self) -> Vec<WorkerStatus> {
+        #[cfg(feature = "async_io")]
+        let workers = self.workers.lock().await;
+        #[cfg(not(feature = "async_io"))]
+        let workers = self.workers.lock().unwrap();
+
+        let mut out = Vec::with_capacity(workers.len());
+        for w in workers.iter() {
+            #[cfg(feature = "async_io")]
+            let state = *w.state.lock().await;
+            #[cfg(not(feature = "async_io"))]
+            let state = *w.state.lock().unwrap();
+            #[cfg(feature = "async_io")]
+            let last_error = w.last_error.lock().await.clone();
+            #[cfg(not(feature = "async_io"))]
+            let last_error = w.last_error.lock().unwrap().clone();
+
+            out.push(WorkerStatus {
+                name: w.name.clone(),
+                state,
+                iterations: w.iterations.load(Ordering::SeqCst),
+                last_error,
+            });
+        }
+        out
+    }
+
+    /// Sends a control command to the named worker. Returns `false` if no
+    /// worker is registered under that name, or if it has exited and
+    /// dropped its command channel (exited workers stay registered); the
+    /// two cases are not told apart.
+    pub async fn send_command(&self, name: &str, cmd: WorkerCommand) -> bool {
+        #[cfg(feature = "async_io")]
+        let workers = self.workers.lock().await;
+        #[cfg(not(feature = "async_io"))]
+        let workers = self.workers.lock().unwrap();
+
+        match workers.iter().find(|w| w.name == name) {
+            Some(w) => w.tx_cmd.send(cmd).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Renders every worker's current state as a human-readable table.
+    pub async fn format_status_report(&self) -> String {
+        let statuses = self.status().await;
+        let mut report = String::from("worker status:\n");
+        for s in statuses {
+            report.push_str(&format!(
+                "  {:<24} state={:?} iterations={} last_error={}\n",
+                s.name,
+                s.state,
+                s.iterations,
+                s.last_error.as_deref().unwrap_or("-"),
+            ));
+        }
+        report
+    }
+}